@@ -0,0 +1,53 @@
+//! Rendering and tracing the demo scene, the 40 holes make it a good test for culling.
+//!
+//! Compare against another revision with `cargo bench -- --save-baseline before` there
+//! and `cargo bench -- --baseline before` here.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use csg_renderer::{
+    objects::Object,
+    render::{RenderSettings, render},
+    scene::Scene,
+};
+use glam::{Vec3, vec3};
+
+fn demo() -> Scene {
+    Scene::parse(include_str!("../scenes/demo.scene"), "demo.scene").unwrap()
+}
+
+fn trace(c: &mut Criterion) {
+    let scene = demo();
+    // A fan of rays from the camera, a third of them missing the part
+    let rays: Vec<Vec3> = (0..1024)
+        .map(|i| {
+            let (x, y) = ((i % 32) as f32 / 31.0, (i / 32) as f32 / 31.0);
+            (vec3(x * 3.0 - 1.5, y * 3.0 - 1.5, 0.0) - scene.camera.eye).normalize()
+        })
+        .collect();
+    c.bench_function("trace demo 1024 rays", |b| {
+        b.iter(|| {
+            rays.iter()
+                .map(|&direction| scene.object.trace(scene.camera.eye, direction).count())
+                .sum::<usize>()
+        })
+    });
+}
+
+fn render_demo(c: &mut Criterion) {
+    let scene = demo();
+    let settings = RenderSettings {
+        width: 128,
+        height: 128,
+        ..RenderSettings::default()
+    };
+    c.bench_function("render demo 128x128", |b| {
+        b.iter(|| render(&scene, &settings))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = trace, render_demo
+}
+criterion_main!(benches);
@@ -0,0 +1,138 @@
+use glam::{Affine3A, Vec3};
+
+/// Margin [`Aabb::padded`] adds, relative to the size of the box.
+const PADDING: f32 = 1e-4;
+
+/// Axis aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Contains nothing, the identity of [`Aabb::union`].
+    pub const EMPTY: Self = Self {
+        min: Vec3::INFINITY,
+        max: Vec3::NEG_INFINITY,
+    };
+
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    /// Smallest box containing all of `points`.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        points.into_iter().fold(Self::EMPTY, |b, p| Self {
+            min: b.min.min(p),
+            max: b.max.max(p),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        }
+    }
+
+    /// This box with `margin` added on every side.
+    pub fn grow(&self, margin: f32) -> Self {
+        Self {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// This box grown by a small margin, so culling against it cannot drop a ray that
+    /// only grazes it through rounding.
+    pub fn padded(&self) -> Self {
+        self.grow(PADDING * self.size().max_element().max(1.0))
+    }
+
+    /// Whether the whole line through `origin` along `direction` meets the box, not just
+    /// the ray's forward half, as traces report crossings on both sides of the origin.
+    pub fn intersects_line(&self, origin: Vec3, direction: Vec3) -> bool {
+        let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+        for axis in 0..3 {
+            let (o, d) = (origin[axis], direction[axis]);
+            if d == 0.0 {
+                if o < self.min[axis] || o > self.max[axis] {
+                    return false;
+                }
+                continue;
+            }
+            let t1 = (self.min[axis] - o) / d;
+            let t2 = (self.max[axis] - o) / d;
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+        }
+        near <= far
+    }
+
+    /// The eight corners, bit 0, 1 and 2 of the index pick the maximum along X, Y and Z.
+    pub fn corners(&self) -> [Vec3; 8] {
+        std::array::from_fn(|i| {
+            Vec3::new(
+                if i & 1 != 0 { self.max.x } else { self.min.x },
+                if i & 2 != 0 { self.max.y } else { self.min.y },
+                if i & 4 != 0 { self.max.z } else { self.min.z },
+            )
+        })
+    }
+
+    /// Box around this box after `transformation`.
+    pub fn transform(&self, transformation: &Affine3A) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        Self::from_points(
+            self.corners()
+                .map(|corner| transformation.transform_point3(corner)),
+        )
+    }
+
+    /// Box around the part of this box where `normal · p <= d`.
+    pub fn clip(&self, normal: Vec3, d: f32) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let corners = self.corners();
+        let kept = corners.into_iter().filter(|&c| normal.dot(c) <= d);
+        // Where the box's edges cross the plane
+        let crossings = (0..8).flat_map(|i| {
+            (0..3).filter_map(move |axis| {
+                let j = i | 1 << axis;
+                if j == i {
+                    return None;
+                }
+                let (a, b) = (corners[i], corners[j]);
+                let (da, db) = (normal.dot(a) - d, normal.dot(b) - d);
+                ((da <= 0.0) != (db <= 0.0)).then(|| a + (b - a) * (da / (da - db)))
+            })
+        });
+        Self::from_points(kept.chain(crossings))
+    }
+}
@@ -0,0 +1,126 @@
+//! Bounding volume hierarchy over a list of boxes, finding the ones a line passes
+//! through without testing each of them.
+
+use glam::Vec3;
+
+use crate::aabb::Aabb;
+
+/// Boxes per leaf, below this splitting costs more box tests than it saves.
+const LEAF_SIZE: usize = 2;
+
+#[derive(Clone, Debug)]
+enum Node {
+    /// Range of [`Bvh::items`].
+    Leaf { start: usize, end: usize },
+    /// The first child directly follows its parent, only the second is stored.
+    Inner { second: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct Bvh {
+    nodes: Vec<(Aabb, Node)>,
+    /// Indices of the boxes, grouped by leaf.
+    items: Vec<usize>,
+    boxes: Vec<Aabb>,
+}
+
+impl Bvh {
+    /// Builds the hierarchy over `boxes`, reported back by their index.
+    pub fn new(boxes: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(2 * boxes.len()),
+            items: (0..boxes.len()).collect(),
+            boxes: boxes.to_vec(),
+        };
+        if !boxes.is_empty() {
+            bvh.build(0, boxes.len());
+        }
+        bvh
+    }
+
+    /// Splits the items in `start..end` at the median along the longest axis of their
+    /// centers.
+    fn build(&mut self, start: usize, end: usize) {
+        let boxes = &self.boxes;
+        let items = &mut self.items[start..end];
+        let bounds = items.iter().fold(Aabb::EMPTY, |b, &i| b.union(&boxes[i]));
+        let node = self.nodes.len();
+        if items.len() <= LEAF_SIZE {
+            self.nodes.push((bounds, Node::Leaf { start, end }));
+            return;
+        }
+        let centers = Aabb::from_points(items.iter().map(|&i| boxes[i].center()));
+        let axis = centers.size().max_position();
+        let middle = items.len() / 2;
+        items.select_nth_unstable_by(middle, |&a, &b| {
+            boxes[a].center()[axis].total_cmp(&boxes[b].center()[axis])
+        });
+        self.nodes.push((bounds, Node::Inner { second: 0 }));
+        self.build(start, start + middle);
+        let second = self.nodes.len();
+        self.nodes[node].1 = Node::Inner { second };
+        self.build(start + middle, end);
+    }
+
+    /// Calls `f` with the index of every box the line through `origin` along
+    /// `direction` passes through.
+    pub fn visit_line(&self, origin: Vec3, direction: Vec3, mut f: impl FnMut(usize)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        // Median splits keep the depth logarithmic, so this is plenty
+        let mut stack = [0; 64];
+        let mut len = 1;
+        while len > 0 {
+            len -= 1;
+            let node = stack[len];
+            let (bounds, node_kind) = &self.nodes[node];
+            if !bounds.intersects_line(origin, direction) {
+                continue;
+            }
+            match *node_kind {
+                Node::Leaf { start, end } => self.items[start..end]
+                    .iter()
+                    .filter(|&&i| self.boxes[i].intersects_line(origin, direction))
+                    .for_each(|&i| f(i)),
+                Node::Inner { second } => {
+                    stack[len] = second;
+                    stack[len + 1] = node + 1;
+                    len += 2;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{aabb::Aabb, bvh::Bvh, testing::scattered};
+
+    #[test]
+    fn matches_brute_force() {
+        let boxes: Vec<Aabb> = (0..50)
+            .map(|i| {
+                let center = scattered(i) * 10.0;
+                Aabb::new(center - 0.3, center + 0.3)
+            })
+            .collect();
+        let bvh = Bvh::new(&boxes);
+        for j in 0..200 {
+            let origin = vec3((j % 10) as f32, (j / 10 % 10) as f32, -1.0);
+            let direction = vec3(0.1 * (j % 7) as f32 - 0.3, 0.05 * (j % 5) as f32, 1.0);
+            let mut visited = vec![];
+            bvh.visit_line(origin, direction, |i| visited.push(i));
+            visited.sort();
+            let expected: Vec<usize> = (0..boxes.len())
+                .filter(|&i| boxes[i].intersects_line(origin, direction))
+                .collect();
+            assert_eq!(visited, expected);
+        }
+        let mut visited = 0;
+        bvh.visit_line(Vec3::splat(20.0), Vec3::X, |_| visited += 1);
+        assert_eq!(visited, 0);
+    }
+}
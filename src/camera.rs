@@ -0,0 +1,300 @@
+//! Camera models, each turning a point of the image into a primary ray.
+//!
+//! Image points are given as `x` and `y` in -1..1, from left to right and from bottom
+//! to top, along with the image's width / height aspect ratio.
+
+use std::f32::consts::PI;
+
+use glam::{Affine3A, Vec3, vec3};
+
+use crate::aabb::Aabb;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Unit length.
+    pub direction: Vec3,
+}
+
+pub trait Camera: Send + Sync {
+    /// Primary ray through the image point `(x, y)`, `None` when the point lies outside
+    /// of what the projection covers.
+    fn ray(&self, x: f32, y: f32, aspect_ratio: f32) -> Option<Ray>;
+
+    /// What the depth pass stores for a hit at `t` along `ray`.
+    fn depth(&self, _ray: &Ray, t: f32) -> f32 {
+        t
+    }
+}
+
+/// Camera space to world space, with X to the right, Y up and Z forward.
+fn view(eye: Vec3, target: Vec3, up: Vec3) -> Affine3A {
+    Affine3A::look_at_lh(eye, target, up).inverse()
+}
+
+/// Pinhole camera.
+pub struct Perspective {
+    view: Affine3A,
+    focal_length: f32,
+}
+
+impl Perspective {
+    /// `fov` is the vertical field of view in degrees.
+    pub fn new(eye: Vec3, target: Vec3, up: Vec3, fov: f32) -> Self {
+        Self {
+            view: view(eye, target, up),
+            focal_length: 1.0 / (fov.to_radians() / 2.0).tan(),
+        }
+    }
+}
+
+impl Camera for Perspective {
+    fn ray(&self, x: f32, y: f32, aspect_ratio: f32) -> Option<Ray> {
+        Some(Ray {
+            origin: self.view.translation.into(),
+            direction: self
+                .view
+                .transform_vector3(vec3(x * aspect_ratio, y, self.focal_length))
+                .normalize(),
+        })
+    }
+
+    /// Distance along the viewing direction rather than along the ray, so flat
+    /// surfaces facing the camera have a constant depth.
+    fn depth(&self, ray: &Ray, t: f32) -> f32 {
+        t * ray
+            .direction
+            .dot(self.view.transform_vector3(Vec3::Z).normalize())
+    }
+}
+
+/// Parallel projection, for engineering views without perspective distortion.
+pub struct Orthographic {
+    view: Affine3A,
+    half_height: f32,
+}
+
+impl Orthographic {
+    /// `height` is the extent of the scene the image covers vertically.
+    pub fn new(eye: Vec3, target: Vec3, up: Vec3, height: f32) -> Self {
+        Self {
+            view: view(eye, target, up),
+            half_height: height / 2.0,
+        }
+    }
+}
+
+impl Camera for Orthographic {
+    fn ray(&self, x: f32, y: f32, aspect_ratio: f32) -> Option<Ray> {
+        let offset = vec3(x * aspect_ratio, y, 0.0) * self.half_height;
+        Some(Ray {
+            origin: self.view.transform_point3(offset),
+            direction: self.view.transform_vector3(Vec3::Z).normalize(),
+        })
+    }
+}
+
+/// Equidistant fisheye, the angle from the viewing direction grows linearly towards
+/// the edge of a circle inscribed in the image.
+pub struct Fisheye {
+    view: Affine3A,
+    half_fov: f32,
+}
+
+impl Fisheye {
+    /// `fov` is the angle in degrees covered by the circle's diameter, up to 360.
+    pub fn new(eye: Vec3, target: Vec3, up: Vec3, fov: f32) -> Self {
+        Self {
+            view: view(eye, target, up),
+            half_fov: fov.to_radians() / 2.0,
+        }
+    }
+}
+
+impl Camera for Fisheye {
+    fn ray(&self, x: f32, y: f32, aspect_ratio: f32) -> Option<Ray> {
+        // The circle fills the shorter side of the image
+        let (x, y) = if aspect_ratio >= 1.0 {
+            (x * aspect_ratio, y)
+        } else {
+            (x, y / aspect_ratio)
+        };
+        let r = (x * x + y * y).sqrt();
+        if r > 1.0 {
+            return None;
+        }
+        let (sin, cos) = (r * self.half_fov).sin_cos();
+        let (dx, dy) = if r == 0.0 { (0.0, 0.0) } else { (x / r, y / r) };
+        Some(Ray {
+            origin: self.view.translation.into(),
+            direction: self
+                .view
+                .transform_vector3(vec3(dx * sin, dy * sin, cos))
+                .normalize(),
+        })
+    }
+}
+
+/// 360° panorama, longitude along the image's width and latitude along its height.
+/// Images with a 2:1 aspect ratio have square pixels.
+pub struct Equirectangular {
+    view: Affine3A,
+}
+
+impl Equirectangular {
+    pub fn new(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        Self {
+            view: view(eye, target, up),
+        }
+    }
+}
+
+impl Camera for Equirectangular {
+    fn ray(&self, x: f32, y: f32, _aspect_ratio: f32) -> Option<Ray> {
+        let (sin_lon, cos_lon) = (x * PI).sin_cos();
+        let (sin_lat, cos_lat) = (y * PI / 2.0).sin_cos();
+        Some(Ray {
+            origin: self.view.translation.into(),
+            direction: self
+                .view
+                .transform_vector3(vec3(cos_lat * sin_lon, sin_lat, cos_lat * cos_lon))
+                .normalize(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Projection {
+    Perspective,
+    Orthographic,
+    Fisheye,
+    Equirectangular,
+}
+
+/// Everything needed to build a [`Camera`], as read from a scene file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraSettings {
+    pub eye: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub projection: Projection,
+    /// Vertical field of view in degrees for perspective cameras, the angle covered
+    /// by the image circle for fisheye cameras.
+    pub fov: f32,
+    /// Extent of the scene covered vertically by orthographic cameras.
+    pub height: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            eye: vec3(3.0, 3.0, 0.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            projection: Projection::Perspective,
+            // Image plane at distance 2 spanning -1..1 vertically
+            fov: 2.0 * 0.5f32.atan().to_degrees(),
+            height: 2.0,
+        }
+    }
+}
+
+impl CameraSettings {
+    pub fn camera(&self) -> Box<dyn Camera> {
+        let (eye, target, up) = (self.eye, self.target, self.up);
+        match self.projection {
+            Projection::Perspective => Box::new(Perspective::new(eye, target, up, self.fov)),
+            Projection::Orthographic => Box::new(Orthographic::new(eye, target, up, self.height)),
+            Projection::Fisheye => Box::new(Fisheye::new(eye, target, up, self.fov)),
+            Projection::Equirectangular => Box::new(Equirectangular::new(eye, target, up)),
+        }
+    }
+
+    /// Aims at the center of `bounds` and backs off along the current viewing direction
+    /// until the whole box fits into an image with the given aspect ratio.
+    pub fn frame(&mut self, bounds: Aabb, aspect_ratio: f32) {
+        let radius = bounds.size().length() / 2.0;
+        let backwards = (self.eye - self.target).try_normalize().unwrap_or(Vec3::Z);
+        let narrow = aspect_ratio.min(1.0);
+        let half_angle = match self.projection {
+            Projection::Perspective => ((self.fov.to_radians() / 2.0).tan() * narrow).atan(),
+            Projection::Fisheye => (self.fov.to_radians() / 2.0).min(PI / 2.0),
+            Projection::Orthographic | Projection::Equirectangular => PI / 4.0,
+        };
+        self.target = bounds.center();
+        self.eye = self.target + backwards * radius / half_angle.sin();
+        self.height = 2.0 * radius / narrow;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{
+        aabb::Aabb,
+        camera::{Camera, CameraSettings, Equirectangular, Fisheye, Orthographic, Perspective},
+    };
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!(a.distance(b) < 1e-5, "{a} != {b}");
+    }
+
+    #[test]
+    fn center_looks_at_target() {
+        let (eye, target) = (vec3(0.0, 0.0, -5.0), Vec3::ZERO);
+        let cameras: [Box<dyn Camera>; 4] = [
+            Box::new(Perspective::new(eye, target, Vec3::Y, 60.0)),
+            Box::new(Orthographic::new(eye, target, Vec3::Y, 2.0)),
+            Box::new(Fisheye::new(eye, target, Vec3::Y, 180.0)),
+            Box::new(Equirectangular::new(eye, target, Vec3::Y)),
+        ];
+        for camera in cameras {
+            let ray = camera.ray(0.0, 0.0, 2.0).unwrap();
+            assert_close(ray.origin, eye);
+            assert_close(ray.direction, Vec3::Z);
+        }
+    }
+
+    #[test]
+    fn projections() {
+        let (eye, target) = (vec3(0.0, 0.0, -5.0), Vec3::ZERO);
+        // Top edge of a 90° perspective view is 45° up
+        let ray = Perspective::new(eye, target, Vec3::Y, 90.0)
+            .ray(0.0, 1.0, 1.0)
+            .unwrap();
+        assert_close(ray.direction, vec3(0.0, 1.0, 1.0).normalize());
+
+        // Orthographic rays are parallel, offset by half the height at the top edge
+        let ray = Orthographic::new(eye, target, Vec3::Y, 4.0)
+            .ray(0.5, 1.0, 2.0)
+            .unwrap();
+        assert_close(ray.origin, vec3(2.0, 2.0, -5.0));
+        assert_close(ray.direction, Vec3::Z);
+
+        // The edge of a 180° fisheye circle looks sideways, the corners are outside
+        let fisheye = Fisheye::new(eye, target, Vec3::Y, 180.0);
+        assert_close(fisheye.ray(0.0, 1.0, 1.0).unwrap().direction, Vec3::Y);
+        assert_eq!(fisheye.ray(1.0, 1.0, 1.0), None);
+
+        // Panorama edges look backwards, the top edge straight up
+        let panorama = Equirectangular::new(eye, target, Vec3::Y);
+        assert_close(panorama.ray(1.0, 0.0, 2.0).unwrap().direction, Vec3::NEG_Z);
+        assert_close(panorama.ray(0.3, 1.0, 2.0).unwrap().direction, Vec3::Y);
+    }
+
+    #[test]
+    fn frame() {
+        let bounds = Aabb::new(vec3(1.0, 1.0, 1.0), vec3(3.0, 5.0, 3.0));
+        let mut settings = CameraSettings {
+            fov: 90.0,
+            ..CameraSettings::default()
+        };
+        settings.frame(bounds, 1.0);
+        assert_close(settings.target, vec3(2.0, 3.0, 2.0));
+        // Bounding sphere of radius 2.45 seen under 45°, along the old direction
+        let offset = settings.eye - settings.target;
+        assert!((offset.length() - 24f32.sqrt() / 2.0 / 45f32.to_radians().sin()).abs() < 1e-4);
+        assert_close(offset.normalize(), vec3(1.0, 1.0, 0.0).normalize());
+    }
+}
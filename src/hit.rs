@@ -0,0 +1,161 @@
+use std::{cmp::Ordering, iter::Peekable};
+
+use glam::Vec3;
+
+use crate::material::Material;
+
+/// A point where a ray crosses the boundary of a solid, as consumed by the range iterators.
+pub trait Boundary: Copy {
+    fn t(&self) -> f32;
+
+    /// The same boundary seen from the complement of the solid that produced it.
+    fn flip(self) -> Self;
+
+    /// This boundary with the surface attributes of `other`, used to give the faces
+    /// a cutter leaves behind the look of the solid they were cut from.
+    fn with_surface_of(self, _other: &Self) -> Self {
+        self
+    }
+}
+
+impl Boundary for f32 {
+    fn t(&self) -> f32 {
+        *self
+    }
+
+    fn flip(self) -> Self {
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub t: f32,
+    /// Outward facing surface normal of the solid at this crossing.
+    pub normal: Vec3,
+    /// Whether the ray enters the solid here, as opposed to leaving it.
+    pub entering: bool,
+    /// Where the crossing lies in the space of the primitive that produced it.
+    pub point: Vec3,
+    /// ID of the primitive that produced this crossing, 0 when it was never tagged.
+    pub id: u32,
+    /// Material of the innermost painted node above the primitive, `None` when unpainted.
+    pub material: Option<Material>,
+}
+
+impl Hit {
+    /// An entry at `t`, primitives pass their crossings through [`crossings`] to get
+    /// `entering` and `point` right.
+    pub fn new(t: f32, normal: Vec3) -> Self {
+        Self {
+            t,
+            normal,
+            entering: true,
+            point: Vec3::ZERO,
+            id: 0,
+            material: None,
+        }
+    }
+}
+
+impl Boundary for Hit {
+    fn t(&self) -> f32 {
+        self.t
+    }
+
+    fn flip(self) -> Self {
+        Self {
+            normal: -self.normal,
+            entering: !self.entering,
+            ..self
+        }
+    }
+
+    fn with_surface_of(self, other: &Self) -> Self {
+        Self {
+            material: other.material,
+            ..self
+        }
+    }
+}
+
+/// Which of the next boundaries of two operands lie nearest, both of them on a tie.
+pub fn nearest<B: Boundary>(a: Option<&B>, b: Option<&B>) -> (bool, bool) {
+    match (a, b) {
+        (Some(a), Some(b)) => match a.t().partial_cmp(&b.t()) {
+            Some(Ordering::Less) => (true, false),
+            Some(Ordering::Greater) => (false, true),
+            _ => (true, true),
+        },
+        (a, b) => (a.is_some(), b.is_some()),
+    }
+}
+
+/// Consumes the next boundary of `iterator` along with any others at the same distance,
+/// toggling `inside` for each. Returns the last of them when the ray ends up on the
+/// other side of the solid, and `None` when they cancel out, as where two intervals touch.
+pub fn cross<I>(iterator: &mut Peekable<I>, inside: &mut bool) -> Option<I::Item>
+where
+    I: Iterator,
+    I::Item: Boundary,
+{
+    let first = iterator.next()?;
+    let t = first.t();
+    let mut last = Some(first);
+    *inside = !*inside;
+    while let Some(next) = iterator.next_if(|b| b.t() == t) {
+        last = if last.is_some() { None } else { Some(next) };
+        *inside = !*inside;
+    }
+    last
+}
+
+/// The crossings of a primitive, at most `N` of them, kept inline so tracing a
+/// primitive does not allocate.
+#[derive(Clone, Copy, Debug)]
+pub struct Crossings<const N: usize> {
+    hits: [Hit; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> Crossings<N> {
+    pub fn empty() -> Self {
+        Self {
+            hits: [Hit::new(0.0, Vec3::ZERO); N],
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Crossings<N> {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        if self.next == self.len {
+            return None;
+        }
+        self.next += 1;
+        Some(self.hits[self.next - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.len - self.next;
+        (left, Some(left))
+    }
+}
+
+/// Finishes the sorted crossings of a primitive traced from `origin` along `direction`:
+/// they alternate between entries and exits, starting with an entry. Panics when there
+/// are more than `N` of them.
+pub fn crossings<const N: usize>(hits: &[Hit], origin: Vec3, direction: Vec3) -> Crossings<N> {
+    let mut crossings = Crossings::empty();
+    crossings.hits[..hits.len()].copy_from_slice(hits);
+    crossings.len = hits.len();
+    for (i, hit) in crossings.hits[..hits.len()].iter_mut().enumerate() {
+        hit.entering = i % 2 == 0;
+        hit.point = origin + direction * hit.t;
+    }
+    crossings
+}
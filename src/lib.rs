@@ -0,0 +1,21 @@
+pub mod aabb;
+pub mod bvh;
+pub mod camera;
+pub mod hit;
+pub mod mass;
+pub mod material;
+pub mod mesh;
+pub mod objects;
+pub mod polynomial;
+pub mod range_difference;
+pub mod range_intersect;
+pub mod range_union;
+pub mod range_vec_difference;
+pub mod range_vec_intersect;
+pub mod range_vec_union;
+pub mod range_xor;
+pub mod render;
+pub mod scene;
+pub mod scratch;
+#[cfg(test)]
+mod testing;
@@ -1,112 +1,455 @@
-use std::f32;
-
-use glam::{Affine3A, Vec3, vec3};
-use image::RgbImage;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-
-use crate::{
-    objects::{
-        Object, clipplane::CSGClipplane, cylinder::CSGCylinder, difference::CSGDifference,
-        sphere::CSGSphere, transform::CSGTransform, vec_union::CSGVecUnion,
-    },
-    range_intersect::RangeIntersect,
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
 };
 
-pub mod objects;
-pub mod range_difference;
-pub mod range_intersect;
-pub mod range_union;
-pub mod range_vec_union;
+use clap::{Parser, Subcommand, ValueEnum};
+use csg_renderer::{
+    aabb::Aabb,
+    camera::Projection,
+    mass::mass_properties,
+    mesh::extract,
+    objects::Object,
+    render::{RenderMode, RenderSettings, Sampling, render, render_with_aovs},
+    scene::{Scene, SceneError},
+};
+use glam::{Vec3, vec3};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Mode {
+    /// Lit materials
+    Shaded,
+    /// World space normals as colors
+    Normals,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ProjectionArg {
+    /// Pinhole camera
+    Perspective,
+    /// Parallel rays, for engineering views
+    Orthographic,
+    /// Equidistant fisheye filling a circle
+    Fisheye,
+    /// 360° panorama, best at a 2:1 aspect ratio
+    Equirectangular,
+}
+
+/// Renders a CSG scene to a PNG image
+#[derive(Parser, Debug)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Scene file to render, the built-in demo part when omitted
+    scene: Option<PathBuf>,
+
+    /// Where to write the rendered image
+    #[arg(short, long, default_value = "output.png")]
+    output: PathBuf,
+
+    /// Image width in pixels
+    #[arg(long, default_value_t = 1024, value_parser = clap::value_parser!(u32).range(1..))]
+    width: u32,
+
+    /// Image height in pixels
+    #[arg(long, default_value_t = 1024, value_parser = clap::value_parser!(u32).range(1..))]
+    height: u32,
+
+    /// Camera position, overrides the scene's camera
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true)]
+    eye: Option<Vec3>,
+
+    /// Point the camera looks at, overrides the scene's camera
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true)]
+    target: Option<Vec3>,
+
+    /// Camera up direction, overrides the scene's camera
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true)]
+    up: Option<Vec3>,
+
+    /// Vertical field of view in degrees (fisheye: angle covered by the image circle),
+    /// overrides the scene's camera
+    #[arg(long)]
+    fov: Option<f32>,
+
+    /// Camera projection, overrides the scene's camera
+    #[arg(long, value_enum)]
+    projection: Option<ProjectionArg>,
+
+    /// Extent of the scene covered vertically by an orthographic camera
+    #[arg(long, value_name = "H")]
+    ortho_height: Option<f32>,
+
+    /// Point light position, may be repeated. Replaces the scene's lights
+    #[arg(long = "light", value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true)]
+    lights: Vec<Vec3>,
+
+    /// What to render
+    #[arg(long, value_enum, default_value_t = Mode::Shaded)]
+    mode: Mode,
+
+    /// Supersample every pixel with an N×N grid of rays
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    samples: u32,
+
+    /// Jitter each supersample randomly inside its grid cell
+    #[arg(long)]
+    jitter: bool,
+
+    /// Only supersample pixels on silhouettes and depth discontinuities
+    #[arg(long)]
+    adaptive: bool,
+
+    /// Light every surface facing a light, even when something is in the way
+    #[arg(long)]
+    no_shadows: bool,
+
+    /// Aim the camera at the scene's bounds and back off until everything is in view
+    #[arg(long)]
+    frame: bool,
+
+    /// Also write a 16-bit depth pass
+    #[arg(long, value_name = "PATH")]
+    depth: Option<PathBuf>,
+
+    /// Depth written as white in the depth pass, the farthest visible point by default
+    #[arg(long, value_name = "F")]
+    depth_far: Option<f32>,
+
+    /// Also write a world space normal pass
+    #[arg(long, value_name = "PATH")]
+    normals: Option<PathBuf>,
+
+    /// Also write a 16-bit pass with the ID of the primitive seen through each pixel
+    #[arg(long, value_name = "PATH")]
+    ids: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Exports a scene's solid as a triangle mesh
+    Mesh(MeshArgs),
+    /// Prints the volume, surface area and mass properties of a scene's solid
+    Volume(VolumeArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct MeshArgs {
+    /// Scene file to export, the built-in demo part when omitted
+    scene: Option<PathBuf>,
+
+    /// Where to write the mesh, as OBJ when the extension is .obj and binary STL otherwise
+    #[arg(short, long, default_value = "output.stl")]
+    output: PathBuf,
+
+    /// Lowest corner of the region to mesh, the scene's bounds by default
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true, requires = "max")]
+    min: Option<Vec3>,
+
+    /// Highest corner of the region to mesh
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true, requires = "min")]
+    max: Option<Vec3>,
+
+    /// Number of grid cells along the longest side of the region
+    #[arg(long, value_name = "N", default_value_t = 64, value_parser = clap::value_parser!(u32).range(1..))]
+    resolution: u32,
+}
 
-const WIDTH: u32 = 1024;
-const HEIGHT: u32 = 1024;
+#[derive(clap::Args, Debug)]
+struct VolumeArgs {
+    /// Scene file to measure, the built-in demo part when omitted
+    scene: Option<PathBuf>,
 
-const ASPECT_RATIO: f32 = WIDTH as f32 / HEIGHT as f32;
+    /// Lowest corner of the region to measure, the scene's bounds by default
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true, requires = "max")]
+    min: Option<Vec3>,
+
+    /// Highest corner of the region to measure
+    #[arg(long, value_name = "X,Y,Z", value_parser = parse_vec3, allow_hyphen_values = true, requires = "min")]
+    max: Option<Vec3>,
+
+    /// Number of rays along the longest side of the region
+    #[arg(long, value_name = "N", default_value_t = 256, value_parser = clap::value_parser!(u32).range(2..))]
+    resolution: u32,
+
+    /// Mass per unit volume
+    #[arg(long, default_value_t = 1.0)]
+    density: f32,
+}
+
+fn parse_vec3(s: &str) -> Result<Vec3, String> {
+    let components = s
+        .split(',')
+        .map(|c| c.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{e} in `{s}`"))?;
+    match components[..] {
+        [x, y, z] => Ok(vec3(x, y, z)),
+        _ => Err(format!(
+            "expected three comma separated numbers, found `{s}`"
+        )),
+    }
+}
+
+fn load_scene(path: Option<&PathBuf>) -> Result<Scene, SceneError> {
+    match path {
+        Some(path) => Scene::load(path),
+        None => Scene::parse(include_str!("../scenes/demo.scene"), "demo.scene"),
+    }
+}
 
 fn main() {
-    let light = vec3(2.0, 2.0, -2.0) * 100.0;
-    let camera = Affine3A::look_at_lh(
-        vec3(3.0, 3.0, 0.0),
-        vec3(0.0, 0.0, 0.0),
-        vec3(0.0, 1.0, 0.0),
-    )
-    .inverse();
-
-    let camera_origin: Vec3 = camera.translation.into();
-
-    let o = {
-        let sphere = CSGSphere::new(Vec3::ZERO, 1.0);
-        let planar_holes: CSGVecUnion<_> = CSGVecUnion::new(
-            (0..5)
-                .map(|i| {
-                    let transform = Affine3A::from_rotation_x(-f32::consts::PI / 4.0 * (i as f32));
-                    let cylinder = CSGCylinder::new(0.2, 1.0);
-                    CSGTransform::new(cylinder, transform)
-                })
-                .collect(),
-        );
+    let args = Args::parse();
+    match args.command {
+        Some(Command::Mesh(ref mesh_args)) => export_mesh(mesh_args),
+        Some(Command::Volume(ref volume_args)) => measure(volume_args),
+        None => render_image(args),
+    }
+}
 
-        let holes = CSGVecUnion::new(
-            (0..8)
-                .map(|i| {
-                    let planar_holes = planar_holes.clone();
-                    let transform =
-                        Affine3A::from_rotation_y(f32::consts::PI * 2.0 / 8.0 * (i as f32));
+/// The box spanned by `min` and `max` when given, the scene's bounds otherwise.
+fn region(scene: &Scene, min: Option<Vec3>, max: Option<Vec3>) -> Aabb {
+    if let (Some(min), Some(max)) = (min, max) {
+        return Aabb::new(min, max);
+    }
+    match scene.object.bounds() {
+        Some(bounds) if !bounds.is_empty() => bounds,
+        Some(_) => {
+            eprintln!("the scene is empty");
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("the scene is unbounded, pass --min and --max");
+            std::process::exit(1);
+        }
+    }
+}
 
-                    CSGTransform::new(planar_holes, transform)
-                })
-                .collect(),
-        );
-        CSGClipplane::new(CSGDifference::new(sphere, holes), vec3(0.0, 1.0, 0.0), 0.0)
+fn export_mesh(args: &MeshArgs) {
+    let scene = match load_scene(args.scene.as_ref()) {
+        Ok(scene) => scene,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
     };
+    let mesh = extract(
+        &scene.object,
+        region(&scene, args.min, args.max),
+        args.resolution,
+    );
+    let extension = args.output.extension().and_then(|e| e.to_str());
+    let result = File::create(&args.output).and_then(|file| {
+        let mut w = BufWriter::new(file);
+        match extension {
+            Some(e) if e.eq_ignore_ascii_case("obj") => mesh.write_obj(&mut w)?,
+            _ => mesh.write_stl(&mut w)?,
+        }
+        w.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("{}: {e}", args.output.display());
+        std::process::exit(1);
+    }
+}
 
-    let buf: Vec<Vec<Option<Vec3>>> = (0..HEIGHT)
-        .into_par_iter()
-        .map(|y| {
-            (0..WIDTH)
-                .into_par_iter()
-                .map(|x| {
-                    let x = x as f32 / WIDTH as f32;
-                    let y = y as f32 / HEIGHT as f32;
-                    let x = (x - 0.5) * 2.0 * ASPECT_RATIO;
-                    let y = ((1.0 - y) - 0.5) * 2.0;
-                    let direction = camera.transform_vector3(Vec3::new(x, y, 2.0)).normalize();
-                    let i = o.trace(camera_origin, direction);
-                    let mut i = RangeIntersect::new(i, vec![0.0, f32::INFINITY].into_iter());
-                    i.next().map(|d| direction * d + camera_origin)
-                })
-                .collect()
-        })
-        .collect();
-    let mut img = RgbImage::new(WIDTH, HEIGHT);
-    img.par_enumerate_pixels_mut().for_each(|(x, y, p)| {
-        let v = buf[y as usize][x as usize];
-        let v = match v {
-            Some(v) => v,
-            None => {
-                *p = image::Rgb([0, 0, 0]);
-                return;
-            }
+fn measure(args: &VolumeArgs) {
+    let scene = match load_scene(args.scene.as_ref()) {
+        Ok(scene) => scene,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let p = mass_properties(
+        &scene.object,
+        region(&scene, args.min, args.max),
+        args.resolution,
+        args.density,
+    );
+    println!("volume          {} ± {}", p.volume.value, p.volume.error);
+    println!(
+        "surface area    {} ± {}",
+        p.surface_area.value, p.surface_area.error
+    );
+    println!("mass            {}", p.mass);
+    let c = p.center_of_mass;
+    println!("center of mass  {} {} {}", c.x, c.y, c.z);
+    println!("inertia tensor  (about the center of mass)");
+    for row in 0..3 {
+        let r = p.inertia.row(row);
+        println!("    {:>12.6} {:>12.6} {:>12.6}", r.x, r.y, r.z);
+    }
+}
+
+fn render_image(args: Args) {
+    let mut scene = match load_scene(args.scene.as_ref()) {
+        Ok(scene) => scene,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    if let Some(eye) = args.eye {
+        scene.camera.eye = eye;
+    }
+    if let Some(target) = args.target {
+        scene.camera.target = target;
+    }
+    if let Some(up) = args.up {
+        scene.camera.up = up;
+    }
+    if let Some(fov) = args.fov {
+        scene.camera.fov = fov;
+    }
+    if let Some(projection) = args.projection {
+        scene.camera.projection = match projection {
+            ProjectionArg::Perspective => Projection::Perspective,
+            ProjectionArg::Orthographic => Projection::Orthographic,
+            ProjectionArg::Fisheye => Projection::Fisheye,
+            ProjectionArg::Equirectangular => Projection::Equirectangular,
         };
-        let normal = {
-            let vx = buf[y as usize]
-                .get(x as usize + 1)
-                .unwrap_or(&None)
-                .unwrap_or(v);
-            let vy = buf
-                .get(y as usize + 1)
-                .map(|v| v[x as usize])
-                .unwrap_or(None)
-                .unwrap_or(v);
-            (v - vx).cross(v - vy).normalize()
+    }
+    if scene.camera.projection == Projection::Perspective && scene.camera.fov >= 180.0 {
+        eprintln!(
+            "perspective fov must be below 180, found {}",
+            scene.camera.fov
+        );
+        std::process::exit(1);
+    }
+    if let Some(height) = args.ortho_height {
+        scene.camera.height = height;
+    }
+    if !args.lights.is_empty() {
+        scene.lights = args.lights;
+    }
+    if args.frame {
+        let bounds = region(&scene, None, None);
+        scene
+            .camera
+            .frame(bounds, args.width as f32 / args.height as f32);
+    }
+    let settings = RenderSettings {
+        width: args.width,
+        height: args.height,
+        mode: match args.mode {
+            Mode::Shaded => RenderMode::Shaded,
+            Mode::Normals => RenderMode::Normals,
+        },
+        samples: args.samples,
+        sampling: if args.jitter {
+            Sampling::Jitter
+        } else {
+            Sampling::Grid
+        },
+        adaptive: args.adaptive,
+        shadows: !args.no_shadows,
+        depth_far: args.depth_far,
+    };
+    if args.depth.is_none() && args.normals.is_none() && args.ids.is_none() {
+        save(&args.output, render(&scene, &settings).save(&args.output));
+    } else {
+        if args.ids.is_some() && scene.primitives > u16::MAX as u32 {
+            eprintln!(
+                "{} primitives, the ID pass can tell at most {} apart",
+                scene.primitives,
+                u16::MAX
+            );
+            std::process::exit(1);
+        }
+        let (image, aovs) = render_with_aovs(&scene, &settings);
+        save(&args.output, image.save(&args.output));
+        if let Some(path) = &args.depth {
+            save(path, aovs.depth.save(path));
+        }
+        if let Some(path) = &args.normals {
+            save(path, aovs.normals.save(path));
+        }
+        if let (Some(path), Some(ids)) = (&args.ids, &aovs.ids) {
+            save(path, ids.save(path));
+        }
+    }
+}
+
+fn save(path: &Path, result: image::ImageResult<()>) {
+    if let Err(e) = result {
+        eprintln!("{}: {e}", path.display());
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use glam::vec3;
+
+    use crate::{Args, Command, parse_vec3};
+
+    #[test]
+    fn vectors() {
+        assert_eq!(parse_vec3("1,2,3"), Ok(vec3(1.0, 2.0, 3.0)));
+        assert_eq!(parse_vec3("-1.5, 0 ,2e1"), Ok(vec3(-1.5, 0.0, 20.0)));
+        assert_eq!(
+            parse_vec3("1,2"),
+            Err("expected three comma separated numbers, found `1,2`".to_owned())
+        );
+        assert!(parse_vec3("1,2,3,4").is_err());
+        assert!(parse_vec3("1,x,3").is_err());
+    }
+
+    #[test]
+    fn negative_vectors() {
+        let args = Args::try_parse_from([
+            "csg-renderer",
+            "--eye",
+            "-3,2,-1",
+            "--target",
+            "-0.5,0,0",
+            "--up",
+            "0,-1,0",
+            "--light",
+            "-10,5,-10",
+            "--light",
+            "10,5,10",
+        ])
+        .unwrap();
+        assert_eq!(args.eye, Some(vec3(-3.0, 2.0, -1.0)));
+        assert_eq!(args.target, Some(vec3(-0.5, 0.0, 0.0)));
+        assert_eq!(args.up, Some(vec3(0.0, -1.0, 0.0)));
+        assert_eq!(
+            args.lights,
+            vec![vec3(-10.0, 5.0, -10.0), vec3(10.0, 5.0, 10.0)]
+        );
+
+        let args = Args::try_parse_from([
+            "csg-renderer",
+            "mesh",
+            "--min",
+            "-1,-1,-1",
+            "--max",
+            "1,1,1",
+        ])
+        .unwrap();
+        let Some(Command::Mesh(mesh)) = args.command else {
+            panic!("{:?}", args.command);
         };
-        let diffuse = (light - v).normalize().dot(normal).clamp(0.0, 1.0);
-        let color = Vec3::ONE * (diffuse + 0.01).powf(1.0 / 2.2);
-        *p = image::Rgb([
-            (color.x.clamp(0.0, 1.0) * 255.0) as u8,
-            (color.y.clamp(0.0, 1.0) * 255.0) as u8,
-            (color.z.clamp(0.0, 1.0) * 255.0) as u8,
+        assert_eq!(mesh.min, Some(vec3(-1.0, -1.0, -1.0)));
+
+        let args = Args::try_parse_from([
+            "csg-renderer",
+            "volume",
+            "--min",
+            "-2,-2,-2",
+            "--max",
+            "-1,0,0",
         ])
-    });
-    img.save("output.png").unwrap();
+        .unwrap();
+        let Some(Command::Volume(volume)) = args.command else {
+            panic!("{:?}", args.command);
+        };
+        assert_eq!(volume.max, Some(vec3(-1.0, 0.0, 0.0)));
+    }
 }
@@ -0,0 +1,241 @@
+//! Volume, surface area and mass properties, integrated from the inside intervals of
+//! a parallel grid of rays.
+//!
+//! Each ray stands for the cell of the grid around it, so the volume is the sum of the
+//! interval lengths times the cell area. Along a ray the moments of the intervals are
+//! integrated exactly, only the cross-section is sampled. Every estimate is compared
+//! against one at half the resolution, and the difference is reported as its error.
+
+use glam::{DMat3, DVec3, Mat3, Vec3};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{aabb::Aabb, objects::Object};
+
+/// A value along with an estimate of its absolute error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    pub value: f32,
+    pub error: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MassProperties {
+    pub volume: Estimate,
+    pub surface_area: Estimate,
+    pub mass: f32,
+    pub center_of_mass: Vec3,
+    /// Inertia tensor about the center of mass.
+    pub inertia: Mat3,
+}
+
+/// Volume integrals of 1, r and r rᵀ.
+#[derive(Clone, Copy)]
+struct Moments {
+    volume: f64,
+    first: DVec3,
+    second: DMat3,
+}
+
+impl Default for Moments {
+    // Not derived, glam's matrices default to the identity
+    fn default() -> Self {
+        Self {
+            volume: 0.0,
+            first: DVec3::ZERO,
+            second: DMat3::ZERO,
+        }
+    }
+}
+
+impl std::ops::Add for Moments {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            volume: self.volume + other.volume,
+            first: self.first + other.first,
+            second: self.second + other.second,
+        }
+    }
+}
+
+/// A grid of rays along `axis`, one through the center of every cell of the bounds'
+/// cross-section. Returns the rays' origins on the bounds' lower face and the cell area.
+fn rays(bounds: Aabb, axis: usize, resolution: u32) -> (Vec<Vec3>, f64) {
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let size = bounds.size();
+    let cell = size.max_element() / resolution.max(1) as f32;
+    let nu = (size[u] / cell).ceil().max(1.0) as u32;
+    let nv = (size[v] / cell).ceil().max(1.0) as u32;
+    let (du, dv) = (size[u] / nu as f32, size[v] / nv as f32);
+    let origins = (0..nu * nv)
+        .map(|i| {
+            let mut origin = bounds.min;
+            origin[u] += ((i % nu) as f32 + 0.5) * du;
+            origin[v] += ((i / nu) as f32 + 0.5) * dv;
+            origin
+        })
+        .collect();
+    (origins, du as f64 * dv as f64)
+}
+
+fn moments<O: Object + Sync>(obj: &O, bounds: Aabb, resolution: u32) -> Moments {
+    let (origins, area) = rays(bounds, 2, resolution);
+    let depth = bounds.size().z;
+    let m = origins
+        .into_par_iter()
+        .map(|origin| {
+            let (x, y) = (origin.x as f64, origin.y as f64);
+            let mut m = Moments::default();
+            let mut hits = obj.trace(origin, Vec3::Z);
+            while let (Some(entry), Some(exit)) = (hits.next(), hits.next()) {
+                let z0 = (entry.t.max(0.0) + bounds.min.z) as f64;
+                let z1 = (exit.t.min(depth) + bounds.min.z) as f64;
+                if z1 <= z0 {
+                    continue;
+                }
+                let l = z1 - z0;
+                let z = (z1 * z1 - z0 * z0) / 2.0;
+                let zz = (z1 * z1 * z1 - z0 * z0 * z0) / 3.0;
+                m.volume += l;
+                m.first += DVec3::new(x * l, y * l, z);
+                m.second += DMat3::from_cols(
+                    DVec3::new(x * x * l, x * y * l, x * z),
+                    DVec3::new(x * y * l, y * y * l, y * z),
+                    DVec3::new(x * z, y * z, zz),
+                );
+            }
+            m
+        })
+        .reduce(Moments::default, |a, b| a + b);
+    Moments {
+        volume: m.volume * area,
+        first: m.first * area,
+        second: m.second * area,
+    }
+}
+
+/// Surface area from rays along all three axes. A patch with normal `n` is crossed by
+/// rays along axis `a` in proportion to `|n[a]|`, so weighting each crossing by the
+/// cell area over `|n.x| + |n.y| + |n.z|` adds up to the patch's area, without the
+/// blow-up a single axis has at grazing angles.
+fn area<O: Object + Sync>(obj: &O, bounds: Aabb, resolution: u32) -> f64 {
+    (0..3)
+        .map(|axis| {
+            let (origins, area) = rays(bounds, axis, resolution);
+            let depth = bounds.size()[axis];
+            let direction = Vec3::AXES[axis];
+            origins
+                .into_par_iter()
+                .map(|origin| {
+                    obj.trace(origin, direction)
+                        .filter(|hit| hit.t >= 0.0 && hit.t <= depth)
+                        .map(|hit| area / hit.normal.abs().element_sum().max(1.0) as f64)
+                        .sum::<f64>()
+                })
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+fn estimate(full: f64, half: f64) -> Estimate {
+    Estimate {
+        value: full as f32,
+        error: (full - half).abs() as f32,
+    }
+}
+
+/// Volume of the part of `obj` inside `bounds`, sampled with `resolution` rays along
+/// the longest side of the bounds.
+pub fn volume<O: Object + Sync>(obj: &O, bounds: Aabb, resolution: u32) -> Estimate {
+    estimate(
+        moments(obj, bounds, resolution).volume,
+        moments(obj, bounds, resolution / 2).volume,
+    )
+}
+
+/// Surface area of the part of `obj` inside `bounds`.
+pub fn surface_area<O: Object + Sync>(obj: &O, bounds: Aabb, resolution: u32) -> Estimate {
+    estimate(
+        area(obj, bounds, resolution),
+        area(obj, bounds, resolution / 2),
+    )
+}
+
+/// Mass properties of the part of `obj` inside `bounds`, made of a material with
+/// uniform `density`.
+pub fn mass_properties<O: Object + Sync>(
+    obj: &O,
+    bounds: Aabb,
+    resolution: u32,
+    density: f32,
+) -> MassProperties {
+    let m = moments(obj, bounds, resolution);
+    let half = moments(obj, bounds, resolution / 2);
+    let center = if m.volume > 0.0 {
+        m.first / m.volume
+    } else {
+        DVec3::ZERO
+    };
+    // Parallel axis theorem, then I = tr(S) E - S for S the integral of r rᵀ
+    let s = m.second
+        - DMat3::from_cols(center * center.x, center * center.y, center * center.z) * m.volume;
+    let trace = s.x_axis.x + s.y_axis.y + s.z_axis.z;
+    let inertia = (DMat3::from_diagonal(DVec3::splat(trace)) - s) * density as f64;
+    MassProperties {
+        volume: estimate(m.volume, half.volume),
+        surface_area: surface_area(obj, bounds, resolution),
+        mass: (m.volume * density as f64) as f32,
+        center_of_mass: center.as_vec3(),
+        inertia: inertia.as_mat3(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use glam::{Vec3, vec3};
+
+    use crate::{
+        aabb::Aabb,
+        mass::{mass_properties, surface_area, volume},
+        objects::{cuboid::CSGBox, sphere::CSGSphere},
+    };
+
+    #[test]
+    fn sphere() {
+        let sphere = CSGSphere::new(vec3(1.0, 2.0, 3.0), 1.0);
+        let bounds = Aabb::new(vec3(0.0, 1.0, 2.0), vec3(2.0, 3.0, 4.0));
+        let v = volume(&sphere, bounds, 128);
+        let expected = 4.0 / 3.0 * PI;
+        assert!((v.value - expected).abs() < 1e-3 * expected, "{v:?}");
+        assert!(v.error < 1e-2 * expected, "{v:?}");
+        let a = surface_area(&sphere, bounds, 128);
+        assert!((a.value - 4.0 * PI).abs() < 1e-2 * 4.0 * PI, "{a:?}");
+    }
+
+    #[test]
+    fn box_mass_properties() {
+        let size = vec3(1.0, 2.0, 4.0);
+        let b = CSGBox::new(Vec3::ONE, Vec3::ONE + size);
+        let bounds = Aabb::new(Vec3::ZERO, Vec3::splat(6.0));
+        let p = mass_properties(&b, bounds, 96, 2.0);
+        let mass = 2.0 * 8.0;
+        assert!((p.mass - mass).abs() < 1e-3, "{p:?}");
+        assert!(
+            p.center_of_mass.distance(Vec3::ONE + size / 2.0) < 1e-3,
+            "{p:?}"
+        );
+        let [x, y, z] = (size * size).to_array();
+        let expected = vec3(y + z, x + z, x + y) * mass / 12.0;
+        let diagonal = vec3(p.inertia.x_axis.x, p.inertia.y_axis.y, p.inertia.z_axis.z);
+        // The cross-section is sampled at cell centers, which is off by h²/12 per cell
+        assert!(
+            (diagonal - expected).abs().max_element() < 2e-3 * expected.max_element(),
+            "{p:?}"
+        );
+        assert!(p.inertia.x_axis.y.abs() < 1e-3, "{p:?}");
+        assert!((p.surface_area.value - 28.0).abs() < 0.1, "{p:?}");
+    }
+}
@@ -0,0 +1,40 @@
+use glam::Vec3;
+
+/// Surface appearance, carried by every [`Hit`](crate::hit::Hit) of a painted solid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    /// Linear diffuse color.
+    pub color: Vec3,
+    /// 0 for a mirror-like highlight, 1 for a very broad one.
+    pub roughness: f32,
+    /// Strength of the specular highlight.
+    pub specular: f32,
+    /// Linear light emitted by the surface, added regardless of the lights.
+    pub emission: Vec3,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            color: Vec3::ONE,
+            roughness: 0.5,
+            specular: 0.0,
+            emission: Vec3::ZERO,
+        }
+    }
+}
+
+impl Material {
+    pub fn new(color: Vec3) -> Self {
+        Self {
+            color,
+            ..Self::default()
+        }
+    }
+
+    /// Blinn-Phong exponent matching `roughness`.
+    pub fn shininess(&self) -> f32 {
+        let alpha = (self.roughness * self.roughness).max(1e-3);
+        2.0 / (alpha * alpha) - 2.0
+    }
+}
@@ -0,0 +1,251 @@
+//! Triangle meshes of solids, for 3D printers and CAD tools.
+//!
+//! The surface is extracted with marching cubes on a regular grid. Every cube is split
+//! into six tetrahedra sharing its main diagonal, which avoids the ambiguous cases of
+//! the classic cube table and still matches up between neighboring cubes, so the mesh
+//! is closed. Vertices are not interpolated but placed where a ray along the grid edge
+//! actually crosses the surface.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use glam::{UVec3, Vec3};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{aabb::Aabb, objects::Object};
+
+/// Indexed triangle mesh, triangles are counterclockwise seen from the outside.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+/// The six tetrahedra of a cube, as indices of its corners where bit 0, 1 and 2 of a
+/// corner's index are its offset along X, Y and Z.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 1, 5, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 5, 7],
+    [0, 4, 6, 7],
+];
+
+struct Grid {
+    origin: Vec3,
+    cell: f32,
+    /// Number of grid points along each axis.
+    points: UVec3,
+}
+
+impl Grid {
+    fn index(&self, p: UVec3) -> usize {
+        (p.x + self.points.x * (p.y + self.points.y * p.z)) as usize
+    }
+
+    fn position(&self, p: UVec3) -> Vec3 {
+        self.origin + p.as_vec3() * self.cell
+    }
+}
+
+/// Classifies every point of the grid.
+fn classify<O: Object + Sync>(obj: &O, grid: &Grid) -> Vec<bool> {
+    let points = grid.points;
+    (0..points.x * points.y * points.z)
+        .into_par_iter()
+        .map(|i| {
+            let p = UVec3::new(
+                i % points.x,
+                i / points.x % points.y,
+                i / (points.x * points.y),
+            );
+            obj.contains(grid.position(p))
+        })
+        .collect()
+}
+
+/// Extracts the surface of `obj` within `bounds`, using cubic cells with `resolution`
+/// of them along the longest side. The grid gets an extra cell on every side, so
+/// solids that touch the bounds are still closed off.
+pub fn extract<O: Object + Sync>(obj: &O, bounds: Aabb, resolution: u32) -> Mesh {
+    let cell = bounds.size().max_element() / resolution.max(1) as f32;
+    let cells = (bounds.size() / cell).ceil().as_uvec3().max(UVec3::ONE);
+    let grid = Grid {
+        origin: bounds.min - cell,
+        cell,
+        points: cells + 3,
+    };
+    let inside = classify(obj, &grid);
+
+    // Vertices are numbered per crossed grid edge, their positions are traced afterwards
+    let mut edges: Vec<(UVec3, UVec3)> = vec![];
+    let mut edge_vertices: HashMap<(usize, usize), u32> = HashMap::new();
+    let mut triangles = vec![];
+    for z in 0..grid.points.z - 1 {
+        for y in 0..grid.points.y - 1 {
+            for x in 0..grid.points.x - 1 {
+                let corners: [UVec3; 8] = std::array::from_fn(|i| {
+                    UVec3::new(x, y, z) + UVec3::new(i as u32 & 1, i as u32 >> 1 & 1, i as u32 >> 2)
+                });
+                let flags = corners.map(|c| inside[grid.index(c)]);
+                if flags.iter().all(|&f| f) || flags.iter().all(|&f| !f) {
+                    continue;
+                }
+                for tetrahedron in TETRAHEDRA {
+                    let (ins, outs): (Vec<UVec3>, Vec<UVec3>) = tetrahedron
+                        .iter()
+                        .map(|&i| corners[i])
+                        .partition(|&c| inside[grid.index(c)]);
+                    let mut vertex = |a: UVec3, b: UVec3| {
+                        *edge_vertices
+                            .entry((grid.index(a), grid.index(b)))
+                            .or_insert_with(|| {
+                                edges.push((a, b));
+                                edges.len() as u32 - 1
+                            })
+                    };
+                    let polygon = match (ins.len(), outs.len()) {
+                        (1, 3) => vec![
+                            vertex(ins[0], outs[0]),
+                            vertex(ins[0], outs[1]),
+                            vertex(ins[0], outs[2]),
+                        ],
+                        (3, 1) => vec![
+                            vertex(ins[0], outs[0]),
+                            vertex(ins[1], outs[0]),
+                            vertex(ins[2], outs[0]),
+                        ],
+                        (2, 2) => vec![
+                            vertex(ins[0], outs[0]),
+                            vertex(ins[0], outs[1]),
+                            vertex(ins[1], outs[1]),
+                            vertex(ins[1], outs[0]),
+                        ],
+                        _ => continue,
+                    };
+                    // Orient by the edge midpoints, facing from the inside corners to
+                    // the outside ones
+                    let midpoint = |v: u32| {
+                        let (a, b) = edges[v as usize];
+                        (grid.position(a) + grid.position(b)) * 0.5
+                    };
+                    let centroid = |points: &[UVec3]| {
+                        points.iter().map(|&p| grid.position(p)).sum::<Vec3>() / points.len() as f32
+                    };
+                    let outward = centroid(&outs) - centroid(&ins);
+                    for i in 1..polygon.len() - 1 {
+                        let triangle = [polygon[0], polygon[i], polygon[i + 1]];
+                        let [a, b, c] = triangle.map(midpoint);
+                        if (b - a).cross(c - a).dot(outward) < 0.0 {
+                            triangles.push([triangle[0], triangle[2], triangle[1]]);
+                        } else {
+                            triangles.push(triangle);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let vertices = edges
+        .par_iter()
+        .map(|&(a, b)| {
+            let (a, b) = (grid.position(a), grid.position(b));
+            let length = a.distance(b);
+            let direction = (b - a) / length;
+            // The crossing closest to the edge, rounding can push it just past an end
+            let outside = |t: f32| (-t).max(t - length).max(0.0);
+            let t = obj
+                .trace(a, direction)
+                .map(|hit| hit.t)
+                .min_by(|&t1, &t2| outside(t1).total_cmp(&outside(t2)))
+                .map_or(length * 0.5, |t| t.clamp(0.0, length));
+            a + direction * t
+        })
+        .collect();
+    Mesh {
+        vertices,
+        triangles,
+    }
+}
+
+impl Mesh {
+    fn corners(&self, triangle: [u32; 3]) -> [Vec3; 3] {
+        triangle.map(|i| self.vertices[i as usize])
+    }
+
+    pub fn write_stl<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut header = [0u8; 80];
+        let text = b"binary STL written by csg-renderer";
+        header[..text.len()].copy_from_slice(text);
+        w.write_all(&header)?;
+        w.write_all(&(self.triangles.len() as u32).to_le_bytes())?;
+        for &triangle in &self.triangles {
+            let [a, b, c] = self.corners(triangle);
+            let normal = (b - a).cross(c - a).normalize_or_zero();
+            for v in [normal, a, b, c] {
+                for component in v.to_array() {
+                    w.write_all(&component.to_le_bytes())?;
+                }
+            }
+            w.write_all(&[0, 0])?;
+        }
+        Ok(())
+    }
+
+    pub fn write_obj<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        for v in &self.vertices {
+            writeln!(w, "v {} {} {}", v.x, v.y, v.z)?;
+        }
+        for [a, b, c] in &self.triangles {
+            writeln!(w, "f {} {} {}", a + 1, b + 1, c + 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use glam::Vec3;
+
+    use crate::{aabb::Aabb, mesh::extract, objects::sphere::CSGSphere};
+
+    #[test]
+    fn sphere_mesh() {
+        let sphere = CSGSphere::new(Vec3::ZERO, 1.0);
+        let mesh = extract(&sphere, Aabb::new(Vec3::splat(-1.0), Vec3::ONE), 24);
+        for v in &mesh.vertices {
+            assert!((v.length() - 1.0).abs() < 1e-4, "{v}");
+        }
+
+        // Closed: every edge is used once in each direction
+        let mut edges: HashMap<(u32, u32), i32> = HashMap::new();
+        for &[a, b, c] in &mesh.triangles {
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                *edges.entry((u.min(v), u.max(v))).or_default() += if u < v { 1 } else { -1 };
+            }
+        }
+        assert!(edges.values().all(|&n| n == 0));
+
+        // Facing outwards: the enclosed volume is positive and close to the sphere's
+        let volume: f32 = mesh
+            .triangles
+            .iter()
+            .map(|&t| {
+                let [a, b, c] = t.map(|i| mesh.vertices[i as usize]);
+                a.dot(b.cross(c)) / 6.0
+            })
+            .sum();
+        let expected = 4.0 / 3.0 * std::f32::consts::PI;
+        assert!((volume - expected).abs() < 0.02 * expected, "{volume}");
+
+        let mut stl = vec![];
+        mesh.write_stl(&mut stl).unwrap();
+        assert_eq!(stl.len(), 84 + 50 * mesh.triangles.len());
+    }
+}
@@ -0,0 +1,48 @@
+use glam::Vec3;
+
+use crate::{aabb::Aabb, objects::Object};
+
+/// Caches the bounding box of `obj` and skips tracing it when the ray misses the box.
+#[derive(Clone)]
+pub struct CSGBounded<O: Object> {
+    obj: O,
+    bounds: Option<Aabb>,
+    /// `bounds` padded, what rays are tested against.
+    culling: Option<Aabb>,
+}
+
+impl<O: Object> CSGBounded<O> {
+    pub fn new(obj: O) -> Self {
+        let bounds = obj.bounds();
+        Self {
+            obj,
+            bounds,
+            culling: bounds.map(|b| b.padded()),
+        }
+    }
+}
+
+impl<O: Object> Object for CSGBounded<O> {
+    type Iter = std::iter::Flatten<std::option::IntoIter<O::Iter>>;
+
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        let missed = self
+            .culling
+            .is_some_and(|b| !b.intersects_line(origin, direction));
+        (!missed)
+            .then(|| self.obj.trace(origin, direction))
+            .into_iter()
+            .flatten()
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let outside = self
+            .culling
+            .is_some_and(|b| point.cmplt(b.min).any() || point.cmpgt(b.max).any());
+        !outside && self.obj.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.bounds
+    }
+}
@@ -1,49 +1,56 @@
 use glam::Vec3;
 
-use crate::{objects::Object, range_intersect::RangeIntersect};
+use crate::{
+    aabb::Aabb,
+    hit::Crossings,
+    objects::{Object, halfspace::CSGHalfSpace},
+    range_intersect::RangeIntersect,
+};
 
+/// Intersection of an object with a half-space, keeping what lies behind the plane.
 pub struct CSGClipplane<O: Object> {
     obj: O,
-    normal: Vec3,
-    d: f32,
+    plane: CSGHalfSpace,
 }
 
 impl<O: Object> Object for CSGClipplane<O> {
-    type Iter = RangeIntersect<O::Iter, std::vec::IntoIter<f32>>;
+    type Iter = RangeIntersect<O::Iter, Crossings<2>>;
 
-    // TODO: Reimplement with own iterator
     fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
-        // <self.normal, origin + direction * t> = self.d
-        // <self.normal, origin> + <self.normal, direction * t> = self.d
-        // <self.normal, origin> + t * <self.normal, direction> = self.d
-        // t * <self.normal, direction> = self.d - <self.normal, origin>
-        // t = ( self.d - <self.normal, origin> )  / <self.normal, direction>
-        let nd = self.normal.dot(direction);
-        if nd == 0.0 {
-            return RangeIntersect::new(
-                self.obj.trace(origin, direction),
-                vec![-f32::INFINITY, f32::INFINITY].into_iter(),
-            );
-        }
-        let threshold = (self.d - self.normal.dot(origin)) / self.normal.dot(direction);
-        let far = if nd < 0.0 {
-            f32::INFINITY
-        } else {
-            -f32::INFINITY
-        };
         RangeIntersect::new(
             self.obj.trace(origin, direction),
-            vec![threshold.min(far), threshold.max(far)].into_iter(),
+            self.plane.trace(origin, direction),
         )
     }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.plane.contains(point) && self.obj.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(self.plane.clip(&self.obj.bounds()?))
+    }
 }
 
 impl<O: Object> CSGClipplane<O> {
     pub fn new(obj: O, normal: Vec3, d: f32) -> Self {
         Self {
             obj,
-            normal: normal.normalize(),
-            d,
+            plane: CSGHalfSpace::new(normal, d),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::objects::{Object, clipplane::CSGClipplane, sphere::CSGSphere};
+
+    #[test]
+    fn parallel_ray() {
+        let clipped = CSGClipplane::new(CSGSphere::new(Vec3::ZERO, 1.0), Vec3::Y, 0.0);
+        assert_eq!(clipped.trace(vec3(-5.0, 0.5, 0.0), Vec3::X).count(), 0);
+        assert_eq!(clipped.trace(vec3(-5.0, -0.5, 0.0), Vec3::X).count(), 2);
+    }
+}
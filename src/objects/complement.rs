@@ -0,0 +1,153 @@
+use std::iter::Peekable;
+
+use glam::Vec3;
+
+use crate::{
+    hit::{Boundary, Hit},
+    objects::Object,
+};
+
+/// Everything outside `obj`. Unbounded, so along every ray it starts inside at -inf
+/// and ends inside at +inf, unless `obj` itself reaches that far.
+#[derive(Clone)]
+pub struct CSGComplement<O: Object> {
+    obj: O,
+}
+
+impl<O: Object> CSGComplement<O> {
+    pub fn new(obj: O) -> Self {
+        Self { obj }
+    }
+}
+
+pub struct ComplementIter<I: Iterator<Item = Hit>> {
+    iterator: Peekable<I>,
+    /// The entry at -inf, until it has been returned.
+    start: Option<Hit>,
+    /// The exit at +inf, until it has been returned.
+    end: Option<Hit>,
+}
+
+impl<I: Iterator<Item = Hit>> ComplementIter<I> {
+    fn new(iterator: I) -> Self {
+        let mut iterator = iterator.peekable();
+        // A solid reaching -inf leaves nothing of its complement before its first exit
+        let start = if iterator.next_if(|hit| hit.t == f32::NEG_INFINITY).is_some() {
+            None
+        } else {
+            Some(Hit::new(f32::NEG_INFINITY, Vec3::ZERO))
+        };
+        Self {
+            iterator,
+            start,
+            end: Some(Hit {
+                entering: false,
+                ..Hit::new(f32::INFINITY, Vec3::ZERO)
+            }),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Hit>> Iterator for ComplementIter<I> {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        if let Some(start) = self.start.take() {
+            return Some(start);
+        }
+        match self.iterator.next() {
+            // Likewise at the other end, this can only be the last crossing
+            Some(hit) if hit.t == f32::INFINITY => {
+                self.end = None;
+                None
+            }
+            Some(hit) => Some(hit.flip()),
+            None => self.end.take(),
+        }
+    }
+}
+
+impl<O: Object> Object for CSGComplement<O> {
+    type Iter = ComplementIter<O::Iter>;
+
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        ComplementIter::new(self.obj.trace(origin, direction))
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        !self.obj.contains(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::objects::{
+        Object, complement::CSGComplement, cuboid::CSGBox, halfspace::CSGHalfSpace,
+        intersect::CSGIntersect, sphere::CSGSphere,
+    };
+
+    fn trace(obj: &impl Object, origin: Vec3, direction: Vec3) -> Vec<(f32, bool)> {
+        obj.trace(origin, direction)
+            .map(|hit| (hit.t, hit.entering))
+            .collect()
+    }
+
+    #[test]
+    fn outside_a_sphere() {
+        let outside = CSGComplement::new(CSGSphere::new(Vec3::ZERO, 1.0));
+        assert_eq!(
+            trace(&outside, vec3(-5.0, 0.0, 0.0), Vec3::X),
+            vec![
+                (f32::NEG_INFINITY, true),
+                (4.0, false),
+                (6.0, true),
+                (f32::INFINITY, false)
+            ]
+        );
+        let normals: Vec<Vec3> = outside
+            .trace(vec3(-5.0, 0.0, 0.0), Vec3::X)
+            .map(|hit| hit.normal)
+            .collect();
+        assert_eq!(normals[1], Vec3::X);
+        assert_eq!(normals[2], Vec3::NEG_X);
+        assert!(outside.contains(vec3(2.0, 0.0, 0.0)));
+        assert!(!outside.contains(Vec3::ZERO));
+    }
+
+    #[test]
+    fn unbounded_operands() {
+        // The complement of a half-space is the opposite half-space
+        let above = CSGComplement::new(CSGHalfSpace::new(Vec3::Y, 0.0));
+        assert_eq!(
+            trace(&above, vec3(0.0, -2.0, 0.0), Vec3::Y),
+            vec![(2.0, true), (f32::INFINITY, false)]
+        );
+        assert_eq!(
+            trace(&above, vec3(0.0, 2.0, 0.0), Vec3::NEG_Y),
+            vec![(f32::NEG_INFINITY, true), (2.0, false)]
+        );
+        assert_eq!(trace(&above, vec3(0.0, -2.0, 0.0), Vec3::X), vec![]);
+        let twice = CSGComplement::new(CSGComplement::new(CSGSphere::new(Vec3::ZERO, 1.0)));
+        assert_eq!(
+            trace(&twice, vec3(-5.0, 0.0, 0.0), Vec3::X),
+            vec![(4.0, true), (6.0, false)]
+        );
+    }
+
+    #[test]
+    fn intersected() {
+        // A box with everything within 1 of its corner removed
+        let carved = CSGIntersect::new(
+            CSGBox::new(Vec3::ZERO, Vec3::splat(2.0)),
+            CSGComplement::new(CSGSphere::new(Vec3::ZERO, 1.0)),
+        );
+        assert_eq!(
+            trace(&carved, vec3(-1.0, 0.5, 0.5), Vec3::X),
+            vec![(1.0 + 0.5f32.sqrt(), true), (3.0, false)]
+        );
+        assert!(!carved.contains(Vec3::splat(0.5)));
+        assert!(carved.contains(Vec3::splat(1.5)));
+    }
+}
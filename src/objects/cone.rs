@@ -0,0 +1,238 @@
+use glam::Vec3;
+
+use crate::{
+    aabb::Aabb,
+    hit::{Crossings, Hit, crossings},
+    objects::Object,
+};
+
+#[derive(Clone)]
+pub struct CSGCone {
+    base_radius: f32,
+    slope: f32,
+    height: f32,
+}
+
+impl CSGCone {
+    /// Cone along the Y axis with `base_radius` at y=0 and `top_radius` at y=`height`.
+    /// A zero `top_radius` gives a pointed cone, anything else a frustum.
+    pub fn new(base_radius: f32, top_radius: f32, height: f32) -> Self {
+        Self {
+            base_radius,
+            slope: (top_radius - base_radius) / height,
+            height,
+        }
+    }
+}
+
+impl Object for CSGCone {
+    type Iter = Crossings<2>;
+
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        // Radius along the ray is w + t * wd, the lateral surface is where
+        // (origin + t * direction).xz().length_squared() == (w + t * wd)^2
+        let w = self.base_radius + self.slope * origin.y;
+        let wd = self.slope * direction.y;
+        let a = direction.x * direction.x + direction.z * direction.z - wd * wd;
+        let b = origin.x * direction.x + origin.z * direction.z - w * wd;
+        let c = origin.x * origin.x + origin.z * origin.z - w * w;
+
+        let mut candidates = [None, None];
+        if a == 0.0 {
+            if b == 0.0 {
+                if c <= 0.0 {
+                    candidates[0] = Some((-f32::INFINITY, f32::INFINITY));
+                }
+            } else if b > 0.0 {
+                candidates[0] = Some((-f32::INFINITY, -c / (2.0 * b)));
+            } else {
+                candidates[0] = Some((-c / (2.0 * b), f32::INFINITY));
+            }
+        } else {
+            let d = b * b - a * c;
+            if d < 0.0 {
+                if a < 0.0 {
+                    candidates[0] = Some((-f32::INFINITY, f32::INFINITY));
+                }
+            } else {
+                let d = d.sqrt();
+                let (r1, r2) = ((-b - d) / a, (-b + d) / a);
+                let (r1, r2) = (r1.min(r2), r1.max(r2));
+                if a > 0.0 {
+                    candidates[0] = Some((r1, r2));
+                } else {
+                    // Ray crosses both nappes of the double cone
+                    candidates[0] = Some((-f32::INFINITY, r1));
+                    candidates[1] = Some((r2, f32::INFINITY));
+                }
+            }
+        }
+
+        // Only the nappe with a non-negative radius belongs to the solid
+        let (n1, n2) = if wd == 0.0 {
+            if w < 0.0 {
+                return Crossings::empty();
+            }
+            (-f32::INFINITY, f32::INFINITY)
+        } else if wd > 0.0 {
+            (-w / wd, f32::INFINITY)
+        } else {
+            (-f32::INFINITY, -w / wd)
+        };
+        let (s1, s2) = if direction.y == 0.0 {
+            if origin.y < 0.0 || origin.y > self.height {
+                return Crossings::empty();
+            }
+            (-f32::INFINITY, f32::INFINITY)
+        } else {
+            let r_base = -origin.y / direction.y;
+            let r_top = (self.height - origin.y) / direction.y;
+            (r_base.min(r_top), r_base.max(r_top))
+        };
+        let hit = |t: f32, cap: f32| {
+            if t == cap {
+                // Entering through the base means travelling up, and vice versa
+                let base = (t == s1) == (direction.y > 0.0);
+                return Hit::new(t, if base { Vec3::NEG_Y } else { Vec3::Y });
+            }
+            let p = origin + direction * t;
+            let radius = self.base_radius + self.slope * p.y;
+            Hit::new(
+                t,
+                Vec3::new(p.x, -self.slope * radius, p.z).normalize_or_zero(),
+            )
+        };
+
+        // The solid is convex, so at most one candidate survives clipping
+        let (near, far) = candidates
+            .into_iter()
+            .flatten()
+            .map(|(r1, r2)| (r1.max(n1).max(s1), r2.min(n2).min(s2)))
+            .filter(|(r1, r2)| r1 <= r2)
+            .fold((f32::INFINITY, -f32::INFINITY), |(near, far), (r1, r2)| {
+                (near.min(r1), far.max(r2))
+            });
+        if near > far {
+            return Crossings::empty();
+        }
+        crossings(&[hit(near, s1), hit(far, s2)], origin, direction)
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let radius = self.base_radius + self.slope * point.y;
+        point.y >= 0.0
+            && point.y <= self.height
+            && radius >= 0.0
+            && point.x * point.x + point.z * point.z <= radius * radius
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let top_radius = self.base_radius + self.slope * self.height;
+        let radius = self.base_radius.max(top_radius).max(0.0);
+        Some(Aabb::new(
+            Vec3::new(-radius, 0.0, -radius),
+            Vec3::new(radius, self.height, radius),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{objects::cone::CSGCone, testing::assert_hits};
+
+    #[test]
+    fn through_the_apex() {
+        // Entering at the apex, which takes the normal of the top cap it lies in
+        let cone = CSGCone::new(1.0, 0.0, 1.0);
+        assert_hits(
+            &cone,
+            vec3(0.0, 3.0, 0.0),
+            Vec3::NEG_Y,
+            &[(2.0, Vec3::Y), (3.0, Vec3::NEG_Y)],
+        );
+        let slanted = vec3(1.0, -2.0, 0.0).normalize();
+        assert_hits(
+            &cone,
+            vec3(-1.0, 3.0, 0.0),
+            slanted,
+            &[(5f32.sqrt(), Vec3::Y), (1.5 * 5f32.sqrt(), Vec3::NEG_Y)],
+        );
+    }
+
+    #[test]
+    fn frustum_side() {
+        let cone = CSGCone::new(1.0, 0.5, 1.0);
+        assert_hits(
+            &cone,
+            vec3(-5.0, 0.5, 0.0),
+            Vec3::X,
+            &[
+                (4.25, vec3(-2.0, 1.0, 0.0).normalize()),
+                (5.75, vec3(2.0, 1.0, 0.0).normalize()),
+            ],
+        );
+    }
+
+    #[test]
+    fn parallel_to_the_surface() {
+        // No slope, so a ray along the axis never meets the side
+        let cone = CSGCone::new(0.5, 0.5, 2.0);
+        assert_hits(
+            &cone,
+            vec3(0.2, 5.0, 0.0),
+            Vec3::NEG_Y,
+            &[(3.0, Vec3::Y), (5.0, Vec3::NEG_Y)],
+        );
+        assert_hits(&cone, vec3(0.6, 5.0, 0.0), Vec3::NEG_Y, &[]);
+        // Parallel to a line of the pointed cone's side, which it crosses only once
+        let cone = CSGCone::new(1.0, 0.0, 1.0);
+        let side = vec3(1.0, -1.0, 0.0).normalize();
+        assert_hits(
+            &cone,
+            vec3(-2.0, 1.5, 0.0),
+            side,
+            &[
+                (1.25 * 2f32.sqrt(), vec3(-1.0, 1.0, 0.0).normalize()),
+                (1.5 * 2f32.sqrt(), Vec3::NEG_Y),
+            ],
+        );
+    }
+
+    #[test]
+    fn single_nappe() {
+        // Widening upwards from its apex at the origin, the vertical ray crosses the
+        // double cone at y = ±0.5, but only the upper nappe is part of the solid
+        let cone = CSGCone::new(0.0, 1.0, 1.0);
+        assert_hits(
+            &cone,
+            vec3(0.5, 5.0, 0.0),
+            Vec3::NEG_Y,
+            &[(4.0, Vec3::Y), (4.5, vec3(1.0, -1.0, 0.0).normalize())],
+        );
+        assert_hits(
+            &cone,
+            vec3(0.5, -5.0, 0.0),
+            Vec3::Y,
+            &[(5.5, vec3(1.0, -1.0, 0.0).normalize()), (6.0, Vec3::Y)],
+        );
+    }
+
+    #[test]
+    fn caps() {
+        let cone = CSGCone::new(1.0, 0.5, 1.0);
+        assert_hits(
+            &cone,
+            vec3(0.2, -5.0, 0.1),
+            Vec3::Y,
+            &[(5.0, Vec3::NEG_Y), (6.0, Vec3::Y)],
+        );
+        assert_hits(
+            &cone,
+            vec3(0.2, 5.0, 0.1),
+            Vec3::NEG_Y,
+            &[(4.0, Vec3::Y), (5.0, Vec3::NEG_Y)],
+        );
+    }
+}
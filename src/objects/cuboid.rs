@@ -0,0 +1,312 @@
+use glam::{Affine3A, Quat, Vec3};
+
+use crate::{
+    aabb::Aabb,
+    hit::{Crossings, Hit, crossings},
+    objects::{Object, transform::CSGTransform},
+};
+
+#[derive(Clone)]
+pub struct CSGBox {
+    min: Vec3,
+    max: Vec3,
+    radius: f32,
+}
+
+impl CSGBox {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+            radius: 0.0,
+        }
+    }
+
+    /// Box spanning `min`..`max` with every edge and corner rounded off by `radius`.
+    pub fn rounded(min: Vec3, max: Vec3, radius: f32) -> Self {
+        let b = Self::new(min, max);
+        let radius = radius.clamp(0.0, ((b.max - b.min) * 0.5).min_element());
+        Self { radius, ..b }
+    }
+
+    /// Box centered at `center` with the given half extents, rotated by `rotation`.
+    pub fn oriented(center: Vec3, half_extents: Vec3, rotation: Quat) -> CSGTransform<Self> {
+        CSGTransform::new(
+            Self::new(-half_extents, half_extents),
+            Affine3A::from_rotation_translation(rotation, center),
+        )
+    }
+}
+
+/// Slab test against the axis aligned box `min`..`max`.
+fn slab(min: Vec3, max: Vec3, origin: Vec3, direction: Vec3) -> Option<(Hit, Hit)> {
+    let mut near = Hit::new(-f32::INFINITY, Vec3::ZERO);
+    let mut far = Hit::new(f32::INFINITY, Vec3::ZERO);
+    for axis in 0..3 {
+        if direction[axis] == 0.0 {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - origin[axis]) / direction[axis];
+        let t2 = (max[axis] - origin[axis]) / direction[axis];
+        let normal = Vec3::AXES[axis] * direction[axis].signum();
+        if t1.min(t2) > near.t {
+            near = Hit::new(t1.min(t2), -normal);
+        }
+        if t1.max(t2) < far.t {
+            far = Hit::new(t1.max(t2), normal);
+        }
+    }
+    if near.t > far.t {
+        None
+    } else {
+        Some((near, far))
+    }
+}
+
+/// Cylinder of `radius` around the line through `center` along `axis`, capped at `lo`..`hi`.
+fn edge(
+    axis: usize,
+    center: Vec3,
+    lo: f32,
+    hi: f32,
+    radius: f32,
+    origin: Vec3,
+    direction: Vec3,
+) -> Option<(Hit, Hit)> {
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let ou = origin[u] - center[u];
+    let ov = origin[v] - center[v];
+    let a = direction[u] * direction[u] + direction[v] * direction[v];
+    let b = ou * direction[u] + ov * direction[v];
+    let c = ou * ou + ov * ov - radius * radius;
+    let (r1, r2) = if a == 0.0 {
+        if c > 0.0 {
+            return None;
+        }
+        (-f32::INFINITY, f32::INFINITY)
+    } else {
+        let d = b * b - a * c;
+        if d < 0.0 {
+            return None;
+        }
+        let d = d.sqrt();
+        ((-b - d) / a, (-b + d) / a)
+    };
+    let side = |t: f32| {
+        let mut normal = origin + direction * t - center;
+        normal[axis] = 0.0;
+        Hit::new(t, normal.normalize_or_zero())
+    };
+    let (h1, h2) = if direction[axis] == 0.0 {
+        if origin[axis] < lo || origin[axis] > hi {
+            return None;
+        }
+        (side(r1), side(r2))
+    } else {
+        let t1 = (lo - origin[axis]) / direction[axis];
+        let t2 = (hi - origin[axis]) / direction[axis];
+        let normal = Vec3::AXES[axis] * direction[axis].signum();
+        (
+            if t1.min(t2) > r1 {
+                Hit::new(t1.min(t2), -normal)
+            } else {
+                side(r1)
+            },
+            if t1.max(t2) < r2 {
+                Hit::new(t1.max(t2), normal)
+            } else {
+                side(r2)
+            },
+        )
+    };
+    if h1.t > h2.t { None } else { Some((h1, h2)) }
+}
+
+fn corner(center: Vec3, radius: f32, origin: Vec3, direction: Vec3) -> Option<(Hit, Hit)> {
+    let oc = origin - center;
+    let a = direction.length_squared();
+    let b = oc.dot(direction);
+    let d = b * b - a * (oc.length_squared() - radius * radius);
+    if d < 0.0 {
+        return None;
+    }
+    let d = d.sqrt();
+    let hit = |t: f32| Hit::new(t, (oc + direction * t) / radius);
+    Some((hit((-b - d) / a), hit((-b + d) / a)))
+}
+
+impl Object for CSGBox {
+    type Iter = Crossings<2>;
+
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        let Some(outer) = slab(self.min, self.max, origin, direction) else {
+            return Crossings::empty();
+        };
+        if self.radius == 0.0 {
+            return crossings(&[outer.0, outer.1], origin, direction);
+        }
+        // A rounded box is the union of three slabs, twelve edge cylinders and eight
+        // corner spheres. It is convex, so the pieces' intervals merge into one.
+        let r = self.radius;
+        let inner_min = self.min + r;
+        let inner_max = self.max - r;
+        let mut near = Hit::new(f32::INFINITY, Vec3::ZERO);
+        let mut far = Hit::new(-f32::INFINITY, Vec3::ZERO);
+        let mut add = |piece: Option<(Hit, Hit)>| {
+            if let Some((h1, h2)) = piece {
+                if h1.t < near.t {
+                    near = h1;
+                }
+                if h2.t > far.t {
+                    far = h2;
+                }
+            }
+        };
+        for axis in 0..3 {
+            let grow = Vec3::AXES[axis] * r;
+            add(slab(inner_min - grow, inner_max + grow, origin, direction));
+            for corner_index in 0..4 {
+                let mut center = inner_min;
+                let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+                if corner_index & 1 != 0 {
+                    center[u] = inner_max[u];
+                }
+                if corner_index & 2 != 0 {
+                    center[v] = inner_max[v];
+                }
+                add(edge(
+                    axis,
+                    center,
+                    inner_min[axis],
+                    inner_max[axis],
+                    r,
+                    origin,
+                    direction,
+                ));
+            }
+        }
+        for corner_index in 0..8 {
+            let center = Vec3::select(
+                glam::BVec3::new(
+                    corner_index & 1 != 0,
+                    corner_index & 2 != 0,
+                    corner_index & 4 != 0,
+                ),
+                inner_max,
+                inner_min,
+            );
+            add(corner(center, r, origin, direction));
+        }
+        if near.t > far.t {
+            return Crossings::empty();
+        }
+        crossings(&[near, far], origin, direction)
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        // Within `radius` of the box shrunk by `radius`
+        let r = self.radius;
+        let nearest = point.clamp(self.min + r, self.max - r);
+        point.distance_squared(nearest) <= r * r
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Aabb::new(self.min, self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{objects::cuboid::CSGBox, testing::assert_hits};
+
+    #[test]
+    fn slabs() {
+        let b = CSGBox::new(Vec3::NEG_ONE, vec3(1.0, 2.0, 1.0));
+        assert_hits(
+            &b,
+            vec3(-5.0, 0.0, 0.0),
+            Vec3::X,
+            &[(4.0, Vec3::NEG_X), (6.0, Vec3::X)],
+        );
+        assert_hits(
+            &b,
+            vec3(0.0, 5.0, 0.0),
+            Vec3::NEG_Y,
+            &[(3.0, Vec3::Y), (6.0, Vec3::NEG_Y)],
+        );
+        // Entering through one face and leaving through another
+        assert_hits(
+            &b,
+            vec3(-2.0, 0.0, -0.5),
+            vec3(1.0, 0.0, 1.0).normalize(),
+            &[(2f32.sqrt(), Vec3::NEG_X), (1.5 * 2f32.sqrt(), Vec3::Z)],
+        );
+    }
+
+    #[test]
+    fn parallel_to_faces() {
+        let b = CSGBox::new(Vec3::NEG_ONE, Vec3::ONE);
+        assert_hits(
+            &b,
+            vec3(-5.0, 0.5, -0.5),
+            Vec3::X,
+            &[(4.0, Vec3::NEG_X), (6.0, Vec3::X)],
+        );
+        assert_hits(&b, vec3(-5.0, 1.5, 0.0), Vec3::X, &[]);
+        assert_hits(&b, vec3(-5.0, 0.0, -2.0), Vec3::X, &[]);
+    }
+
+    #[test]
+    fn rounded_edges_and_corners() {
+        let b = CSGBox::rounded(Vec3::NEG_ONE, Vec3::ONE, 0.5);
+        // Through the edge cylinders around (0.5, ±0.5) along z
+        assert_hits(
+            &b,
+            vec3(0.8, 5.0, 0.0),
+            Vec3::NEG_Y,
+            &[(4.1, vec3(0.6, 0.8, 0.0)), (5.9, vec3(0.6, -0.8, 0.0))],
+        );
+        // Through the corner spheres around (0.5, 0.5, ±0.5)
+        let z = 0.07f32.sqrt();
+        assert_hits(
+            &b,
+            vec3(0.8, 0.8, 5.0),
+            Vec3::NEG_Z,
+            &[
+                (4.5 - z, vec3(0.3, 0.3, z) / 0.5),
+                (5.5 + z, vec3(0.3, 0.3, -z) / 0.5),
+            ],
+        );
+        // The flat faces are left as they are
+        assert_hits(
+            &b,
+            vec3(0.2, -0.3, 5.0),
+            Vec3::NEG_Z,
+            &[(4.0, Vec3::Z), (6.0, Vec3::NEG_Z)],
+        );
+        // Inside the box's corner, but outside the rounding
+        assert_hits(&b, vec3(0.95, 0.95, 5.0), Vec3::NEG_Z, &[]);
+    }
+
+    #[test]
+    fn rounded_radius_clamped() {
+        let b = CSGBox::rounded(Vec3::ZERO, vec3(2.0, 1.0, 4.0), 3.0);
+        assert_eq!(b.radius, 0.5);
+        let b = CSGBox::rounded(Vec3::ZERO, vec3(2.0, 1.0, 4.0), -1.0);
+        assert_eq!(b.radius, 0.0);
+        // Fully rounded, the thin axis becomes a cylinder
+        let b = CSGBox::rounded(Vec3::ZERO, vec3(1.0, 2.0, 2.0), 1.0);
+        assert_hits(
+            &b,
+            vec3(0.5, 1.0, -5.0),
+            Vec3::Z,
+            &[(5.0, Vec3::NEG_Z), (7.0, Vec3::Z)],
+        );
+        assert_hits(&b, vec3(0.1, 0.1, -5.0), Vec3::Z, &[]);
+    }
+}
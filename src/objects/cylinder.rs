@@ -1,6 +1,10 @@
-use glam::Vec3Swizzles;
+use glam::{Vec3, Vec3Swizzles};
 
-use crate::objects::Object;
+use crate::{
+    aabb::Aabb,
+    hit::{Crossings, Hit, crossings},
+    objects::Object,
+};
 
 #[derive(Clone)]
 pub struct CSGCylinder {
@@ -18,23 +22,40 @@ impl CSGCylinder {
 }
 
 impl Object for CSGCylinder {
-    type Iter = std::vec::IntoIter<f32>;
+    type Iter = Crossings<2>;
 
     fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
         let flat_direction = direction.xz();
         let flat_direction_length = flat_direction.length();
-        let flat_direction = direction.xz().normalize();
         let flat_origin = origin.xz();
-        let uoc = (flat_origin).dot(flat_direction);
-        let d = uoc * uoc - flat_origin.length_squared() + self.radius_squared;
-        if d < 0.0 {
-            return vec![].into_iter();
-        }
-        let d = d.sqrt();
-        let r1 = (-uoc - d) / flat_direction_length;
-        let r2 = (-uoc + d) / flat_direction_length;
-        if direction.z == 0.0 {
-            return vec![r1, r2].into_iter();
+        let (r1, r2) = if flat_direction_length == 0.0 {
+            // Parallel to the axis, either always or never inside the side wall
+            if flat_origin.length_squared() > self.radius_squared {
+                return Crossings::empty();
+            }
+            (-f32::INFINITY, f32::INFINITY)
+        } else {
+            let flat_direction = flat_direction / flat_direction_length;
+            let uoc = (flat_origin).dot(flat_direction);
+            let d = uoc * uoc - flat_origin.length_squared() + self.radius_squared;
+            if d < 0.0 {
+                return Crossings::empty();
+            }
+            let d = d.sqrt();
+            (
+                (-uoc - d) / flat_direction_length,
+                (-uoc + d) / flat_direction_length,
+            )
+        };
+        let side = |t: f32| {
+            let p = origin + direction * t;
+            Hit::new(t, Vec3::new(p.x, 0.0, p.z).normalize())
+        };
+        if direction.y == 0.0 {
+            if origin.y < 0.0 || origin.y > self.height {
+                return Crossings::empty();
+            }
+            return crossings(&[side(r1), side(r2)], origin, direction);
         }
         // ( origin + r * direction ).y = x
         //  origin.y + r * direction.y = x
@@ -42,10 +63,63 @@ impl Object for CSGCylinder {
         //  r = ( x-origin.y ) / direction.y
         let r_base = -origin.y / direction.y;
         let r_top = (self.height - origin.y) / direction.y;
-        let (r1, r2) = (r1.max(r_base.min(r_top)), r2.min(r_base.max(r_top)));
-        if r1 > r2 {
-            return vec![].into_iter();
+        let base = Hit::new(r_base, Vec3::NEG_Y);
+        let top = Hit::new(r_top, Vec3::Y);
+        let (cap1, cap2) = if r_base < r_top {
+            (base, top)
+        } else {
+            (top, base)
+        };
+        let h1 = if cap1.t > r1 { cap1 } else { side(r1) };
+        let h2 = if cap2.t < r2 { cap2 } else { side(r2) };
+        if h1.t > h2.t {
+            return Crossings::empty();
         }
-        vec![r1, r2].into_iter()
+        crossings(&[h1, h2], origin, direction)
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        point.xz().length_squared() <= self.radius_squared
+            && point.y >= 0.0
+            && point.y <= self.height
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let radius = self.radius_squared.sqrt();
+        Some(Aabb::new(
+            Vec3::new(-radius, 0.0, -radius),
+            Vec3::new(radius, self.height, radius),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{
+        objects::{cuboid::CSGBox, cylinder::CSGCylinder, difference::CSGDifference},
+        testing::assert_hits,
+    };
+
+    #[test]
+    fn parallel_to_the_axis() {
+        let cylinder = CSGCylinder::new(0.5, 2.0);
+        assert_hits(
+            &cylinder,
+            vec3(0.3, 5.0, 0.0),
+            Vec3::NEG_Y,
+            &[(3.0, Vec3::Y), (5.0, Vec3::NEG_Y)],
+        );
+        assert_hits(&cylinder, vec3(3.0, 3.0, 0.0), Vec3::NEG_Y, &[]);
+
+        // Straight down a hole drilled through a box
+        let drilled = CSGDifference::new(CSGBox::new(Vec3::NEG_ONE, Vec3::ONE), cylinder);
+        assert_hits(
+            &drilled,
+            vec3(0.0, 5.0, 0.0),
+            Vec3::NEG_Y,
+            &[(5.0, Vec3::Y), (6.0, Vec3::NEG_Y)],
+        );
     }
 }
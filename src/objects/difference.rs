@@ -1,4 +1,4 @@
-use crate::{objects::Object, range_difference::RangeDifference};
+use crate::{aabb::Aabb, objects::Object, range_difference::RangeDifference};
 
 #[derive(Clone)]
 pub struct CSGDifference<O1, O2>
@@ -8,6 +8,7 @@ where
 {
     obj1: O1,
     obj2: O2,
+    cutter_material: bool,
 }
 
 impl<O1, O2> CSGDifference<O1, O2>
@@ -16,7 +17,17 @@ where
     O2: Object,
 {
     pub fn new(obj1: O1, obj2: O2) -> Self {
-        Self { obj1, obj2 }
+        Self {
+            obj1,
+            obj2,
+            cutter_material: false,
+        }
+    }
+
+    /// Paint the faces cut by `obj2` with its material instead of the material of `obj1`.
+    pub fn with_cutter_material(mut self) -> Self {
+        self.cutter_material = true;
+        self
     }
 }
 
@@ -30,6 +41,19 @@ where
     fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
         let i1 = self.obj1.trace(origin, direction);
         let i2 = self.obj2.trace(origin, direction);
-        Self::Iter::new(i1, i2)
+        let iter = Self::Iter::new(i1, i2);
+        if self.cutter_material {
+            iter.with_cutter_surface()
+        } else {
+            iter
+        }
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj1.contains(point) && !self.obj2.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.obj1.bounds()
     }
 }
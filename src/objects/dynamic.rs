@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use glam::Vec3;
+
+use crate::{aabb::Aabb, hit::Hit, objects::Object, scratch};
+
+/// Holds the iterator of a concrete object, boxed so it can be recycled once its box
+/// is taken from the scratch pool again.
+struct Slot<I>(Option<I>);
+
+trait ErasedIter: Iterator<Item = Hit> {
+    /// Drops the iterator and hands its box to the scratch pool.
+    fn recycle(self: Box<Self>);
+}
+
+impl<I: Iterator<Item = Hit>> Iterator for Slot<I> {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        self.0.as_mut()?.next()
+    }
+}
+
+impl<I: Iterator<Item = Hit> + 'static> ErasedIter for Slot<I> {
+    fn recycle(mut self: Box<Self>) {
+        self.0 = None;
+        scratch::recycle(self);
+    }
+}
+
+/// The iterator of a [`DynObject`], which reuses its box for later rays.
+pub struct DynIter {
+    /// Only `None` while being dropped.
+    iter: Option<Box<dyn ErasedIter>>,
+}
+
+impl Iterator for DynIter {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        self.iter.as_mut()?.next()
+    }
+}
+
+impl Drop for DynIter {
+    fn drop(&mut self) {
+        if let Some(iter) = self.iter.take() {
+            iter.recycle();
+        }
+    }
+}
+
+trait ErasedObject: Send + Sync {
+    fn trace_erased(&self, origin: Vec3, direction: Vec3) -> DynIter;
+
+    fn contains_erased(&self, point: Vec3) -> bool;
+
+    fn bounds_erased(&self) -> Option<Aabb>;
+}
+
+impl<O> ErasedObject for O
+where
+    O: Object + Send + Sync,
+    O::Iter: 'static,
+{
+    fn trace_erased(&self, origin: Vec3, direction: Vec3) -> DynIter {
+        let mut slot = scratch::take::<Slot<O::Iter>>().unwrap_or_else(|| Box::new(Slot(None)));
+        slot.0 = Some(self.trace(origin, direction));
+        DynIter { iter: Some(slot) }
+    }
+
+    fn contains_erased(&self, point: Vec3) -> bool {
+        self.contains(point)
+    }
+
+    fn bounds_erased(&self) -> Option<Aabb> {
+        self.bounds()
+    }
+}
+
+/// An [`Object`] whose concrete type is only known at runtime.
+#[derive(Clone)]
+pub struct DynObject {
+    obj: Arc<dyn ErasedObject>,
+}
+
+impl DynObject {
+    pub fn new<O>(obj: O) -> Self
+    where
+        O: Object + Send + Sync + 'static,
+        O::Iter: 'static,
+    {
+        Self { obj: Arc::new(obj) }
+    }
+}
+
+impl Object for DynObject {
+    type Iter = DynIter;
+
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        self.obj.trace_erased(origin, direction)
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.obj.contains_erased(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.obj.bounds_erased()
+    }
+
+    fn into_dyn(self) -> DynObject {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::objects::{
+        Object, cylinder::CSGCylinder, difference::CSGDifference, sphere::CSGSphere,
+        transform::CSGTransform, vec_union::CSGVecUnion,
+    };
+
+    #[test]
+    fn mixed_vec_union() {
+        let drilled = CSGDifference::new(
+            CSGSphere::new(vec3(0.0, 0.0, 4.0), 1.0),
+            CSGTransform::new(
+                CSGCylinder::new(0.5, 1.0),
+                glam::Affine3A::from_translation(vec3(0.0, 0.0, 4.0)),
+            ),
+        );
+        let union = CSGVecUnion::new(vec![
+            CSGSphere::new(Vec3::ZERO, 1.0).into_dyn(),
+            CSGCylinder::new(0.5, 1.0).into_dyn(),
+            drilled.into_dyn(),
+        ]);
+        let hits: Vec<f32> = union
+            .trace(vec3(0.0, 0.5, -5.0), Vec3::Z)
+            .map(|hit| hit.t)
+            .collect();
+        let expected = [
+            5.0 - 0.75f32.sqrt(),
+            5.0 + 0.75f32.sqrt(),
+            9.0 - 0.75f32.sqrt(),
+            9.0 - 0.5,
+            9.0 + 0.5,
+            9.0 + 0.75f32.sqrt(),
+        ];
+        assert_eq!(hits.len(), expected.len());
+        for (a, e) in hits.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{hits:?}");
+        }
+    }
+}
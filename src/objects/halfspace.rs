@@ -0,0 +1,72 @@
+use glam::Vec3;
+
+use crate::{
+    aabb::Aabb,
+    hit::{Crossings, Hit, crossings},
+    objects::Object,
+};
+
+/// The points `p` with `<normal, p> <= d`, an unbounded solid whose only face is the
+/// plane, with the normal pointing away from the solid.
+#[derive(Clone, Copy, Debug)]
+pub struct CSGHalfSpace {
+    normal: Vec3,
+    d: f32,
+}
+
+impl CSGHalfSpace {
+    pub fn new(normal: Vec3, d: f32) -> Self {
+        Self {
+            normal: normal.normalize(),
+            d,
+        }
+    }
+
+    /// The part of `bounds` inside the half-space.
+    pub fn clip(&self, bounds: &Aabb) -> Aabb {
+        bounds.clip(self.normal, self.d)
+    }
+}
+
+impl Object for CSGHalfSpace {
+    type Iter = Crossings<2>;
+
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        // <self.normal, origin + direction * t> = self.d
+        // t * <self.normal, direction> = self.d - <self.normal, origin>
+        // t = ( self.d - <self.normal, origin> )  / <self.normal, direction>
+        let nd = self.normal.dot(direction);
+        if nd == 0.0 {
+            // Parallel to the plane, the ray is either entirely inside or entirely outside
+            if self.normal.dot(origin) > self.d {
+                return Crossings::empty();
+            }
+            return crossings(
+                &[
+                    Hit::new(-f32::INFINITY, self.normal),
+                    Hit::new(f32::INFINITY, self.normal),
+                ],
+                origin,
+                direction,
+            );
+        }
+        let threshold = (self.d - self.normal.dot(origin)) / nd;
+        let far = if nd < 0.0 {
+            f32::INFINITY
+        } else {
+            -f32::INFINITY
+        };
+        crossings(
+            &[
+                Hit::new(threshold.min(far), self.normal),
+                Hit::new(threshold.max(far), self.normal),
+            ],
+            origin,
+            direction,
+        )
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.normal.dot(point) <= self.d
+    }
+}
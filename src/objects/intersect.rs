@@ -1,4 +1,4 @@
-use crate::{objects::Object, range_intersect::RangeIntersect};
+use crate::{aabb::Aabb, objects::Object, range_intersect::RangeIntersect};
 
 #[derive(Clone)]
 pub struct CSGIntersect<O1, O2>
@@ -32,4 +32,15 @@ where
         let i2 = self.obj2.trace(origin, direction);
         Self::Iter::new(i1, i2)
     }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj1.contains(point) && self.obj2.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        match (self.obj1.bounds(), self.obj2.bounds()) {
+            (Some(a), Some(b)) => Some(a.intersection(&b)),
+            (a, b) => a.or(b),
+        }
+    }
 }
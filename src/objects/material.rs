@@ -0,0 +1,54 @@
+use crate::{aabb::Aabb, hit::Hit, material::Material, objects::Object};
+
+/// Paints every crossing of `obj` that does not already carry a material, so the
+/// innermost material wins.
+#[derive(Clone)]
+pub struct CSGMaterial<O: Object> {
+    obj: O,
+    material: Material,
+}
+
+impl<O: Object> CSGMaterial<O> {
+    pub fn new(obj: O, material: Material) -> Self {
+        Self { obj, material }
+    }
+}
+
+pub struct MaterialIter<I: Iterator<Item = Hit>> {
+    iterator: I,
+    material: Material,
+}
+
+impl<I: Iterator<Item = Hit>> Iterator for MaterialIter<I> {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        let hit = self.iterator.next()?;
+        if hit.material.is_some() {
+            return Some(hit);
+        }
+        Some(Hit {
+            material: Some(self.material),
+            ..hit
+        })
+    }
+}
+
+impl<O: Object> Object for CSGMaterial<O> {
+    type Iter = MaterialIter<O::Iter>;
+
+    fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
+        MaterialIter {
+            iterator: self.obj.trace(origin, direction),
+            material: self.material,
+        }
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.obj.bounds()
+    }
+}
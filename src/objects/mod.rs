@@ -1,19 +1,178 @@
 use glam::Vec3;
 
+use crate::{aabb::Aabb, hit::Hit, objects::dynamic::DynObject};
+
+pub mod bounded;
 pub mod clipplane;
+pub mod complement;
+pub mod cone;
+pub mod cuboid;
 pub mod cylinder;
 pub mod difference;
+pub mod dynamic;
+pub mod halfspace;
 pub mod intersect;
+pub mod material;
 pub mod sphere;
+pub mod tag;
+pub mod torus;
 pub mod transform;
 pub mod union;
+pub mod vec_difference;
+pub mod vec_intersect;
 pub mod vec_union;
+pub mod xor;
+
+/// Direction of the rays classifying single points, picked so it is unlikely to graze
+/// axis aligned faces.
+const PROBE_DIRECTION: Vec3 = Vec3::new(0.57735, 0.57736, 0.57734);
 
 pub trait Object
 where
-    Self::Iter: Iterator<Item = f32>,
+    Self::Iter: Iterator<Item = Hit>,
 {
     type Iter;
 
     fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter;
+
+    /// Whether `point` lies inside the solid, which overrides take to include the
+    /// boundary. By default, a ray is traced from the point: going outwards, the
+    /// boundaries alternate between exits and entries and end with an exit, so the
+    /// point is inside exactly when an odd number of them lie in front of it. A point on
+    /// the boundary crosses it at a distance of about zero, so rounding decides there.
+    fn contains(&self, point: Vec3) -> bool {
+        self.trace(point, PROBE_DIRECTION)
+            .filter(|hit| hit.t > 0.0)
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Box containing the whole solid, `None` when the solid is unbounded.
+    fn bounds(&self) -> Option<Aabb> {
+        None
+    }
+
+    /// Erases the concrete type, so differently typed objects can share a container.
+    fn into_dyn(self) -> DynObject
+    where
+        Self: Sized + Send + Sync + 'static,
+        Self::Iter: 'static,
+    {
+        DynObject::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{
+        aabb::Aabb,
+        objects::{
+            Object, difference::CSGDifference, sphere::CSGSphere, union::CSGUnion,
+            vec_union::CSGVecUnion,
+        },
+        range_vec_union::RangeVecUnion,
+        scene::Scene,
+        testing::scattered,
+    };
+
+    /// Only forwards `trace`, so the fallbacks of the other methods are used, e.g. ray
+    /// parity for `contains`.
+    struct Parity<O: Object>(O);
+
+    impl<O: Object> Object for Parity<O> {
+        type Iter = O::Iter;
+
+        fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+            self.0.trace(origin, direction)
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let shell = CSGDifference::new(
+            CSGSphere::new(Vec3::ZERO, 1.0),
+            CSGSphere::new(Vec3::ZERO, 0.5),
+        );
+        assert!(!shell.contains(Vec3::ZERO));
+        assert!(shell.contains(vec3(0.0, 0.75, 0.0)));
+        assert!(!shell.contains(vec3(0.0, 0.0, 2.0)));
+        assert!(!Parity(shell).contains(vec3(0.0, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn contains_matches_parity() {
+        let scene = Scene::parse(
+            "object vec_union {\n\
+               clipplane 0 1 0 0.3 { difference { box -1 -1 -1 1 1 1 round 0.2  cylinder 0.5 2 } }\n\
+               transform rotate_x 30 translate 2 0 0 { cone 0.5 0 1 }\n\
+               transform scale 1 2 1 { torus 1 0.25 }\n\
+               intersect { sphere 0 0 0 1.2  vec_difference { sphere 0 0 0 1.5  box 0 0 0 2 2 2 } }\n\
+               xor { sphere -1.5 -1.5 0 0.8  box -1.5 -1.5 -1 -0.5 -0.5 1 }\n\
+               intersect { box 1 -2.5 -2 2.5 -1 2  complement { sphere 1.5 -2 0 0.7 } }\n\
+               intersect { halfspace 1 1 1 -3  complement { halfspace 0 0 1 -2 } }\n\
+             }",
+            "test",
+        )
+        .unwrap();
+        let parity = Parity(scene.object.clone());
+        let mut inside = 0;
+        for i in 0..4000 {
+            let p = scattered(i) * 5.0 - 2.5;
+            let expected = parity.contains(p);
+            assert_eq!(scene.object.contains(p), expected, "{p}");
+            inside += expected as usize;
+        }
+        assert!(inside > 100);
+    }
+
+    #[test]
+    fn bounds() {
+        let scene = Scene::parse(include_str!("../../scenes/demo.scene"), "demo.scene").unwrap();
+        assert_eq!(
+            scene.object.bounds(),
+            Some(Aabb::new(Vec3::splat(-1.0), vec3(1.0, 0.0, 1.0)))
+        );
+
+        let scene = Scene::parse(
+            "object intersect {\n\
+               transform rotate_z 45 { box -1 -1 -1 1 1 1 }\n\
+               clipplane 1 1 0 0 { sphere 0 0 0 1 }\n\
+             }",
+            "test",
+        )
+        .unwrap();
+        let bounds = scene.object.bounds().unwrap();
+        // The clipped sphere's box keeps the corner (-1, -1) and the plane's crossings
+        // of its edges at (1, -1) and (-1, 1)
+        assert!(bounds.min.distance(Vec3::splat(-1.0)) < 1e-5, "{bounds:?}");
+        assert!(bounds.max.distance(Vec3::ONE) < 1e-5, "{bounds:?}");
+
+        let unbounded = Parity(CSGSphere::new(Vec3::ZERO, 1.0));
+        assert_eq!(
+            CSGUnion::new(CSGSphere::new(Vec3::ZERO, 1.0), unbounded).bounds(),
+            None
+        );
+    }
+
+    #[test]
+    fn bvh_union_matches_flat() {
+        let spheres: Vec<CSGSphere> = (0..12)
+            .map(|i| CSGSphere::new(vec3(i as f32, (i % 3) as f32, 0.0), 0.4))
+            .collect();
+        let union = CSGVecUnion::new(spheres.clone());
+        for j in 0..100 {
+            // Including rays that just graze the spheres' boxes
+            let origin = vec3(j as f32 * 0.13 - 1.0, -5.0, j as f32 * 0.01 - 0.4);
+            let direction = vec3(0.1, 1.0, 0.0).normalize();
+            let flat: Vec<f32> =
+                RangeVecUnion::new(spheres.iter().map(|s| s.trace(origin, direction)))
+                    .map(|hit| hit.t)
+                    .collect();
+            let hits: Vec<f32> = union.trace(origin, direction).map(|hit| hit.t).collect();
+            assert_eq!(hits, flat, "{origin}");
+        }
+    }
 }
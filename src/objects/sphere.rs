@@ -1,6 +1,10 @@
 use glam::Vec3;
 
-use crate::objects::Object;
+use crate::{
+    aabb::Aabb,
+    hit::{Crossings, Hit, crossings},
+    objects::Object,
+};
 
 #[derive(Clone)]
 pub struct CSGSphere {
@@ -18,18 +22,32 @@ impl CSGSphere {
 }
 
 impl Object for CSGSphere {
-    type Iter = std::vec::IntoIter<f32>;
+    type Iter = Crossings<2>;
 
     fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
         let uoc = (origin - self.origin).dot(direction);
         let d = uoc * uoc - (origin - self.origin).length_squared() + self.radius_squared;
         if d < 0.0 {
-            vec![].into_iter()
+            Crossings::empty()
         } else {
             let d = d.sqrt();
             let r1 = -uoc - d;
             let r2 = -uoc + d;
-            vec![r1, r2].into_iter()
+            let normal = |t: f32| (origin + direction * t - self.origin).normalize();
+            crossings(
+                &[Hit::new(r1, normal(r1)), Hit::new(r2, normal(r2))],
+                origin,
+                direction,
+            )
         }
     }
+
+    fn contains(&self, point: Vec3) -> bool {
+        (point - self.origin).length_squared() <= self.radius_squared
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let radius = Vec3::splat(self.radius_squared.sqrt());
+        Some(Aabb::new(self.origin - radius, self.origin + radius))
+    }
 }
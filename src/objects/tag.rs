@@ -0,0 +1,51 @@
+use crate::{aabb::Aabb, hit::Hit, objects::Object};
+
+/// Stamps an ID on every crossing of `obj` that does not already carry one,
+/// so the innermost tag wins.
+#[derive(Clone)]
+pub struct CSGTag<O: Object> {
+    obj: O,
+    id: u32,
+}
+
+impl<O: Object> CSGTag<O> {
+    pub fn new(obj: O, id: u32) -> Self {
+        Self { obj, id }
+    }
+}
+
+pub struct TagIter<I: Iterator<Item = Hit>> {
+    iterator: I,
+    id: u32,
+}
+
+impl<I: Iterator<Item = Hit>> Iterator for TagIter<I> {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        let hit = self.iterator.next()?;
+        if hit.id != 0 {
+            return Some(hit);
+        }
+        Some(Hit { id: self.id, ..hit })
+    }
+}
+
+impl<O: Object> Object for CSGTag<O> {
+    type Iter = TagIter<O::Iter>;
+
+    fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
+        TagIter {
+            iterator: self.obj.trace(origin, direction),
+            id: self.id,
+        }
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.obj.bounds()
+    }
+}
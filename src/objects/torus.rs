@@ -0,0 +1,123 @@
+use glam::{DVec3, Vec3};
+
+use crate::{
+    aabb::Aabb,
+    hit::{Crossings, Hit, crossings},
+    objects::Object,
+    polynomial::solve_quartic,
+};
+
+#[derive(Clone)]
+pub struct CSGTorus {
+    major_radius: f32,
+    minor_radius: f32,
+}
+
+impl CSGTorus {
+    /// Torus around the Y axis, centered at the origin.
+    pub fn new(major_radius: f32, minor_radius: f32) -> Self {
+        Self {
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Object for CSGTorus {
+    type Iter = Crossings<4>;
+
+    fn trace(&self, ray_origin: Vec3, ray_direction: Vec3) -> Self::Iter {
+        let origin = ray_origin.as_dvec3();
+        let direction = ray_direction.as_dvec3();
+        let major = self.major_radius as f64;
+        let minor = self.minor_radius as f64;
+
+        // Restart the ray at the point closest to the center, which keeps the
+        // quartic's coefficients small no matter how far away the camera is
+        let alpha = direction.length_squared();
+        let shift = -origin.dot(direction) / alpha;
+        let origin = origin + direction * shift;
+        let bound = major + minor;
+        if origin.length_squared() > bound * bound {
+            return Crossings::empty();
+        }
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (p.x^2 + p.z^2) with p = origin + t * direction
+        let beta = origin.dot(direction);
+        let gamma = origin.length_squared() + major * major - minor * minor;
+        let flat = DVec3::new(1.0, 0.0, 1.0);
+        let four_r2 = 4.0 * major * major;
+        let roots = solve_quartic(
+            alpha * alpha,
+            4.0 * alpha * beta,
+            4.0 * beta * beta + 2.0 * alpha * gamma - four_r2 * (direction * flat).length_squared(),
+            4.0 * beta * gamma - 2.0 * four_r2 * (origin * flat).dot(direction),
+            gamma * gamma - four_r2 * (origin * flat).length_squared(),
+        );
+        let mut hits = [Hit::new(0.0, Vec3::ZERO); 4];
+        for (hit, &t) in hits.iter_mut().zip(roots.iter()) {
+            // Gradient of the implicit surface, up to a constant factor
+            let p = origin + direction * t;
+            let normal = p * (p.length_squared() + major * major - minor * minor)
+                - 2.0 * major * major * p * flat;
+            *hit = Hit::new((t + shift) as f32, normal.as_vec3().normalize_or_zero());
+        }
+        crossings(&hits[..roots.len()], ray_origin, ray_direction)
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let ring = Vec3::new(point.x, 0.0, point.z).length() - self.major_radius;
+        ring * ring + point.y * point.y <= self.minor_radius * self.minor_radius
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3::new(outer, self.minor_radius, outer);
+        Some(Aabb::new(-extent, extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use crate::objects::{Object, torus::CSGTorus};
+
+    #[test]
+    fn torus_four_crossings() {
+        let torus = CSGTorus::new(2.0, 0.5);
+        let crossings: Vec<f32> = torus
+            .trace(Vec3::new(-10.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0))
+            .map(|hit| hit.t)
+            .collect();
+        let expected = [7.5, 8.5, 11.5, 12.5];
+        assert_eq!(crossings.len(), expected.len());
+        for (a, e) in crossings.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{crossings:?}");
+        }
+    }
+
+    #[test]
+    fn torus_through_hole() {
+        let torus = CSGTorus::new(2.0, 0.5);
+        let crossings: Vec<f32> = torus
+            .trace(Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0))
+            .map(|hit| hit.t)
+            .collect();
+        assert_eq!(crossings, vec![]);
+    }
+
+    #[test]
+    fn torus_far_away() {
+        let torus = CSGTorus::new(2.0, 0.5);
+        let crossings: Vec<f32> = torus
+            .trace(Vec3::new(2.0, 0.0, -1000.0), Vec3::new(0.0, 0.0, 1.0))
+            .map(|hit| hit.t)
+            .collect();
+        let expected = [998.5, 1001.5];
+        assert_eq!(crossings.len(), expected.len());
+        for (a, e) in crossings.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-3, "{crossings:?}");
+        }
+    }
+}
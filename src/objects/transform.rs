@@ -1,28 +1,198 @@
-use glam::Affine3A;
+use glam::{Affine3A, Mat3A};
 
-use crate::objects::Object;
+use crate::{aabb::Aabb, hit::Hit, objects::Object};
 
 #[derive(Clone)]
 pub struct CSGTransform<O: Object> {
     obj: O,
     transformation: Affine3A,
+    normal_transformation: Mat3A,
 }
 
 impl<O: Object> CSGTransform<O> {
     pub fn new(obj: O, transformation: Affine3A) -> Self {
+        let transformation = transformation.inverse();
         Self {
             obj,
-            transformation: transformation.inverse(),
+            transformation,
+            // Normals transform with the inverse transpose
+            normal_transformation: transformation.matrix3.transpose(),
         }
     }
 }
 
+pub struct TransformIter<I: Iterator<Item = Hit>> {
+    iterator: I,
+    normal_transformation: Mat3A,
+    /// Length of the traced direction in the space of `obj`, which is traced along the
+    /// unit direction, so its distances are divided by this to get the caller's.
+    direction_length: f32,
+}
+
+impl<I: Iterator<Item = Hit>> Iterator for TransformIter<I> {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        let hit = self.iterator.next()?;
+        Some(Hit {
+            t: hit.t / self.direction_length,
+            normal: (self.normal_transformation * hit.normal).normalize_or_zero(),
+            ..hit
+        })
+    }
+}
+
 impl<O: Object> Object for CSGTransform<O> {
-    type Iter = O::Iter;
+    type Iter = TransformIter<O::Iter>;
 
     fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
         let origin = self.transformation.transform_point3(origin);
-        let direction = self.transformation.transform_vector3(direction).normalize();
-        self.obj.trace(origin, direction)
+        let direction = self.transformation.transform_vector3(direction);
+        let direction_length = direction.length();
+        TransformIter {
+            iterator: self.obj.trace(origin, direction * direction_length.recip()),
+            normal_transformation: self.normal_transformation,
+            direction_length,
+        }
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj
+            .contains(self.transformation.transform_point3(point))
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        // Only the inverse is stored
+        Some(self.obj.bounds()?.transform(&self.transformation.inverse()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Affine3A, Mat3, Vec3, vec3};
+
+    use crate::{
+        objects::{
+            Object, cuboid::CSGBox, cylinder::CSGCylinder, sphere::CSGSphere, torus::CSGTorus,
+            transform::CSGTransform, union::CSGUnion,
+        },
+        testing::scattered,
+    };
+
+    /// Rays from all around the unit cube, aimed at points inside it.
+    fn rays() -> impl Iterator<Item = (Vec3, Vec3)> {
+        (0..300).map(|i| {
+            let origin = scattered(i).with_z(0.5) * 20.0 - 10.0;
+            let target = scattered(i + 300) * 2.0 - 1.0;
+            (origin, (target - origin).normalize())
+        })
+    }
+
+    fn assert_same(transformed: &impl Object, direct: &impl Object) {
+        let mut crossings = 0;
+        for (origin, direction) in rays() {
+            let a: Vec<_> = transformed.trace(origin, direction).collect();
+            let b: Vec<_> = direct.trace(origin, direction).collect();
+            assert_eq!(a.len(), b.len(), "{origin} {direction}");
+            for (a, b) in a.iter().zip(&b) {
+                assert!(
+                    (a.t - b.t).abs() < 1e-4,
+                    "{origin} {direction}: {a:?} {b:?}"
+                );
+                assert!(a.normal.distance(b.normal) < 1e-3, "{a:?} {b:?}");
+                assert_eq!(a.entering, b.entering);
+            }
+            crossings += a.len();
+        }
+        assert!(crossings > 100);
+    }
+
+    #[test]
+    fn scaled_primitives() {
+        assert_same(
+            &CSGTransform::new(
+                CSGSphere::new(Vec3::ZERO, 1.0),
+                Affine3A::from_scale(Vec3::splat(2.0)),
+            ),
+            &CSGSphere::new(Vec3::ZERO, 2.0),
+        );
+        assert_same(
+            &CSGTransform::new(
+                CSGCylinder::new(1.0, 1.0),
+                Affine3A::from_scale(vec3(1.5, 0.5, 1.5)),
+            ),
+            &CSGCylinder::new(1.5, 0.5),
+        );
+        assert_same(
+            &CSGTransform::new(
+                CSGBox::new(Vec3::NEG_ONE, Vec3::ONE),
+                Affine3A::from_scale_rotation_translation(
+                    vec3(3.0, 1.0, 0.5),
+                    Default::default(),
+                    vec3(0.5, 0.0, 0.0),
+                ),
+            ),
+            &CSGBox::new(vec3(-2.5, -1.0, -0.5), vec3(3.5, 1.0, 0.5)),
+        );
+        assert_same(
+            &CSGTransform::new(
+                CSGTorus::new(1.0, 0.25),
+                Affine3A::from_scale(Vec3::splat(0.8)),
+            ),
+            &CSGTorus::new(0.8, 0.2),
+        );
+        // Scales undone by an outer transform
+        assert_same(
+            &CSGTransform::new(
+                CSGTransform::new(
+                    CSGSphere::new(Vec3::ZERO, 1.0),
+                    Affine3A::from_scale(Vec3::splat(4.0)),
+                ),
+                Affine3A::from_scale(vec3(0.25, 0.25, 0.25)),
+            ),
+            &CSGSphere::new(Vec3::ZERO, 1.0),
+        );
+    }
+
+    #[test]
+    fn sheared_crossings_lie_on_the_surface() {
+        let transformation = Affine3A::from_mat3_translation(
+            Mat3::from_cols(
+                vec3(2.0, 0.0, 0.0),
+                vec3(0.7, 0.5, 0.0),
+                vec3(0.0, -0.3, 1.2),
+            ),
+            vec3(0.1, 0.2, 0.0),
+        );
+        let ellipsoid = CSGTransform::new(CSGSphere::new(Vec3::ZERO, 1.0), transformation);
+        let mut crossings = 0;
+        for (origin, direction) in rays() {
+            for hit in ellipsoid.trace(origin, direction) {
+                let world = origin + direction * hit.t;
+                let local = transformation.transform_point3(hit.point);
+                assert!(world.distance(local) < 1e-4, "{world} {local}");
+                crossings += 1;
+            }
+        }
+        assert!(crossings > 100);
+    }
+
+    #[test]
+    fn stretched_union_keeps_order() {
+        // A sphere stretched to reach x = 3, and another one from x = 2.5 to 3.5
+        let union = CSGUnion::new(
+            CSGTransform::new(
+                CSGSphere::new(Vec3::ZERO, 1.0),
+                Affine3A::from_scale(vec3(3.0, 1.0, 1.0)),
+            ),
+            CSGSphere::new(vec3(3.0, 0.0, 0.0), 0.5),
+        );
+        let t: Vec<f32> = union
+            .trace(vec3(-5.0, 0.0, 0.0), Vec3::X)
+            .map(|hit| hit.t)
+            .collect();
+        assert_eq!(t.len(), 2);
+        assert!((t[0] - 2.0).abs() < 1e-5, "{t:?}");
+        assert!((t[1] - 8.5).abs() < 1e-5, "{t:?}");
     }
 }
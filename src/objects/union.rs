@@ -1,4 +1,4 @@
-use crate::{objects::Object, range_union::RangeUnion};
+use crate::{aabb::Aabb, objects::Object, range_union::RangeUnion};
 
 #[derive(Clone)]
 pub struct CSGUnion<O1, O2>
@@ -32,4 +32,12 @@ where
         let i2 = self.obj2.trace(origin, direction);
         Self::Iter::new(i1, i2)
     }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj1.contains(point) || self.obj2.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(self.obj1.bounds()?.union(&self.obj2.bounds()?))
+    }
 }
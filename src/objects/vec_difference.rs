@@ -0,0 +1,61 @@
+use crate::{aabb::Aabb, objects::Object, range_vec_difference::RangeVecDifference};
+
+#[derive(Clone)]
+pub struct CSGVecDifference<O1, O2>
+where
+    O1: Object,
+    O2: Object,
+{
+    base: O1,
+    cutters: Vec<O2>,
+    cutter_material: bool,
+}
+
+impl<O1, O2> CSGVecDifference<O1, O2>
+where
+    O1: Object,
+    O2: Object,
+{
+    pub fn new(base: O1, cutters: Vec<O2>) -> Self {
+        Self {
+            base,
+            cutters,
+            cutter_material: false,
+        }
+    }
+
+    /// Paint the faces cut by the cutters with their materials instead of the material
+    /// of the base.
+    pub fn with_cutter_material(mut self) -> Self {
+        self.cutter_material = true;
+        self
+    }
+}
+
+impl<O1, O2> Object for CSGVecDifference<O1, O2>
+where
+    O1: Object,
+    O2: Object,
+    O2::Iter: 'static,
+{
+    type Iter = RangeVecDifference<O1::Iter, O2::Iter>;
+
+    fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
+        let base = self.base.trace(origin, direction);
+        let cutters = self.cutters.iter().map(|obj| obj.trace(origin, direction));
+        let iter = RangeVecDifference::new(base, cutters);
+        if self.cutter_material {
+            iter.with_cutter_surface()
+        } else {
+            iter
+        }
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.base.contains(point) && !self.cutters.iter().any(|obj| obj.contains(point))
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.base.bounds()
+    }
+}
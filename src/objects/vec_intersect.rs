@@ -0,0 +1,35 @@
+use crate::{aabb::Aabb, objects::Object, range_vec_intersect::RangeVecIntersect};
+
+#[derive(Clone)]
+pub struct CSGVecIntersect<O: Object> {
+    objects: Vec<O>,
+}
+
+impl<O: Object> CSGVecIntersect<O> {
+    pub fn new(objects: Vec<O>) -> Self {
+        Self { objects }
+    }
+}
+
+impl<O> Object for CSGVecIntersect<O>
+where
+    O: Object,
+    O::Iter: 'static,
+{
+    type Iter = RangeVecIntersect<O::Iter>;
+
+    fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
+        RangeVecIntersect::new(self.objects.iter().map(|obj| obj.trace(origin, direction)))
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.objects.iter().all(|obj| obj.contains(point))
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.objects
+            .iter()
+            .filter_map(|obj| obj.bounds())
+            .reduce(|a, b| a.intersection(&b))
+    }
+}
@@ -1,25 +1,73 @@
-use crate::{objects::Object, range_vec_union::RangeVecUnion};
+use glam::Vec3;
+
+use crate::{aabb::Aabb, bvh::Bvh, objects::Object, range_vec_union::RangeVecUnion};
+
+/// Unions with at least this many children only trace the ones whose box the ray meets.
+const BVH_MIN_CHILDREN: usize = 4;
 
 #[derive(Clone)]
 pub struct CSGVecUnion<O: Object> {
     objects: Vec<O>,
+    /// Hierarchy over the bounded children, indexing into `bounded`.
+    bvh: Option<Bvh>,
+    bounded: Vec<usize>,
+    /// Children without bounds, traced by every ray.
+    unbounded: Vec<usize>,
+    bounds: Option<Aabb>,
 }
 
 impl<O: Object> CSGVecUnion<O> {
     pub fn new(objects: Vec<O>) -> Self {
-        Self { objects }
+        let boxes: Vec<Option<Aabb>> = objects.iter().map(Object::bounds).collect();
+        let bounds = boxes
+            .iter()
+            .try_fold(Aabb::EMPTY, |b, child| Some(b.union(&(*child)?)));
+        let (bounded, unbounded): (Vec<usize>, Vec<usize>) =
+            (0..objects.len()).partition(|&i| boxes[i].is_some());
+        let bvh = (bounded.len() >= BVH_MIN_CHILDREN).then(|| {
+            let boxes: Vec<Aabb> = bounded
+                .iter()
+                .map(|&i| boxes[i].unwrap().padded())
+                .collect();
+            Bvh::new(&boxes)
+        });
+        Self {
+            objects,
+            bvh,
+            bounded,
+            unbounded,
+            bounds,
+        }
     }
 }
 
-impl<O: Object> Object for CSGVecUnion<O> {
+impl<O> Object for CSGVecUnion<O>
+where
+    O: Object,
+    O::Iter: 'static,
+{
     type Iter = RangeVecUnion<O::Iter>;
 
-    fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
-        let is: Vec<O::Iter> = self
-            .objects
-            .iter()
-            .map(|obj| obj.trace(origin, direction))
-            .collect();
-        RangeVecUnion::new(is)
+    fn trace(&self, origin: Vec3, direction: Vec3) -> Self::Iter {
+        let Some(bvh) = &self.bvh else {
+            return RangeVecUnion::new(self.objects.iter().map(|obj| obj.trace(origin, direction)));
+        };
+        let mut union = RangeVecUnion::new(
+            self.unbounded
+                .iter()
+                .map(|&i| self.objects[i].trace(origin, direction)),
+        );
+        bvh.visit_line(origin, direction, |i| {
+            union.push(self.objects[self.bounded[i]].trace(origin, direction))
+        });
+        union
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.objects.iter().any(|obj| obj.contains(point))
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.bounds
     }
 }
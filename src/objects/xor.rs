@@ -0,0 +1,43 @@
+use crate::{aabb::Aabb, objects::Object, range_xor::RangeXor};
+
+#[derive(Clone)]
+pub struct CSGXor<O1, O2>
+where
+    O1: Object,
+    O2: Object,
+{
+    obj1: O1,
+    obj2: O2,
+}
+
+impl<O1, O2> CSGXor<O1, O2>
+where
+    O1: Object,
+    O2: Object,
+{
+    pub fn new(obj1: O1, obj2: O2) -> Self {
+        Self { obj1, obj2 }
+    }
+}
+
+impl<O1, O2> Object for CSGXor<O1, O2>
+where
+    O1: Object,
+    O2: Object,
+{
+    type Iter = RangeXor<O1::Iter, O2::Iter>;
+
+    fn trace(&self, origin: glam::Vec3, direction: glam::Vec3) -> Self::Iter {
+        let i1 = self.obj1.trace(origin, direction);
+        let i2 = self.obj2.trace(origin, direction);
+        Self::Iter::new(i1, i2)
+    }
+
+    fn contains(&self, point: glam::Vec3) -> bool {
+        self.obj1.contains(point) != self.obj2.contains(point)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(self.obj1.bounds()?.union(&self.obj2.bounds()?))
+    }
+}
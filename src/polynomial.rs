@@ -0,0 +1,244 @@
+//! Real root finding for the low degree polynomials that come out of ray/surface
+//! intersection. Everything is computed in `f64`, and instead of the closed forms
+//! (which lose most of their precision on badly scaled input) roots are isolated
+//! between the extrema of the polynomial and then bracketed.
+
+use std::{fmt, ops::Deref};
+
+const MAX_ITERATIONS: usize = 100;
+
+/// Highest degree handled, which bounds the number of roots.
+const MAX_DEGREE: usize = 4;
+
+/// Real roots of a polynomial, sorted and kept inline so solving does not allocate.
+#[derive(Clone, Copy, Default)]
+pub struct Roots {
+    roots: [f64; MAX_DEGREE],
+    len: usize,
+}
+
+impl Roots {
+    fn push(&mut self, x: f64) {
+        self.roots[self.len] = x;
+        self.len += 1;
+    }
+}
+
+impl FromIterator<f64> for Roots {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut roots = Self::default();
+        iter.into_iter().for_each(|x| roots.push(x));
+        roots
+    }
+}
+
+impl Deref for Roots {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.roots[..self.len]
+    }
+}
+
+impl fmt::Debug for Roots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+/// Evaluates the polynomial with `coefficients` (highest degree first) and its derivative.
+fn evaluate(coefficients: &[f64], x: f64) -> (f64, f64) {
+    let (mut value, mut derivative) = (0.0, 0.0);
+    for &c in coefficients {
+        derivative = derivative * x + value;
+        value = value * x + c;
+    }
+    (value, derivative)
+}
+
+/// Finds the single root of a polynomial that changes sign exactly once on `lo`..`hi`.
+/// Newton steps are used while they stay inside the bracket, bisection otherwise.
+fn bracketed_root(coefficients: &[f64], mut lo: f64, mut hi: f64) -> f64 {
+    let lo_positive = evaluate(coefficients, lo).0 > 0.0;
+    let mut x = 0.5 * (lo + hi);
+    for _ in 0..MAX_ITERATIONS {
+        let (value, derivative) = evaluate(coefficients, x);
+        if value == 0.0 {
+            return x;
+        }
+        if (value > 0.0) == lo_positive {
+            lo = x;
+        } else {
+            hi = x;
+        }
+        let newton = x - value / derivative;
+        let next = if newton > lo && newton < hi {
+            newton
+        } else {
+            0.5 * (lo + hi)
+        };
+        if next == x || hi - lo <= f64::EPSILON * x.abs() {
+            return next;
+        }
+        x = next;
+    }
+    x
+}
+
+/// Real roots of a polynomial of degree at most [`MAX_DEGREE`] with a non-zero leading
+/// coefficient.
+///
+/// The roots of the derivative split the real line into monotonic pieces, each of
+/// which holds at most one root that can then be found by safe bracketing.
+fn isolate(coefficients: &[f64]) -> Roots {
+    let degree = coefficients.len() - 1;
+    if degree <= 2 {
+        let mut c = [0.0; 3];
+        c[3 - coefficients.len()..].copy_from_slice(coefficients);
+        return solve_quadratic(c[0], c[1], c[2]);
+    }
+    let mut derivative = [0.0; MAX_DEGREE];
+    for (i, c) in coefficients[..degree].iter().enumerate() {
+        derivative[i] = c * (degree - i) as f64;
+    }
+    // Cauchy's bound, no root lies outside of -bound..bound
+    let bound = 1.0
+        + coefficients[1..]
+            .iter()
+            .map(|c| (c / coefficients[0]).abs())
+            .fold(0.0, f64::max);
+    // The bounds and the extrema in between
+    let mut points = [-bound; MAX_DEGREE + 1];
+    let mut len = 1;
+    for &x in isolate(&derivative[..degree]).iter() {
+        if x.abs() < bound {
+            points[len] = x;
+            len += 1;
+        }
+    }
+    points[len] = bound;
+    len += 1;
+    points[..len]
+        .windows(2)
+        .filter(|w| {
+            (evaluate(coefficients, w[0]).0 > 0.0) != (evaluate(coefficients, w[1]).0 > 0.0)
+        })
+        .map(|w| bracketed_root(coefficients, w[0], w[1]))
+        .collect()
+}
+
+/// Real roots of `a x^2 + b x + c`, sorted. Double roots are reported twice.
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Roots {
+    if a == 0.0 {
+        if b == 0.0 {
+            return Roots::default();
+        }
+        return [-c / b].into_iter().collect();
+    }
+    let d = b * b - 4.0 * a * c;
+    if d < 0.0 {
+        return Roots::default();
+    }
+    // Avoid cancellation between -b and the square root
+    let q = -0.5 * (b + b.signum() * d.sqrt());
+    let (r1, r2) = if q == 0.0 { (0.0, 0.0) } else { (q / a, c / q) };
+    [r1.min(r2), r1.max(r2)].into_iter().collect()
+}
+
+/// Real roots of `a x^3 + b x^2 + c x + d`, sorted.
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Roots {
+    if a == 0.0 {
+        return solve_quadratic(b, c, d);
+    }
+    isolate(&[a, b, c, d])
+}
+
+/// Real roots of `a x^4 + b x^3 + c x^2 + d x + e`, sorted.
+///
+/// A root where the polynomial only touches zero is either missed or reported
+/// twice, so a quartic with a positive leading coefficient always yields an even
+/// number of roots, which is what a closed surface needs.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Roots {
+    if a == 0.0 {
+        return solve_cubic(b, c, d, e);
+    }
+    isolate(&[a, b, c, d, e])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::polynomial::{Roots, solve_cubic, solve_quadratic, solve_quartic};
+
+    fn assert_roots(actual: Roots, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn quadratic() {
+        assert_roots(solve_quadratic(1.0, -3.0, 2.0), &[1.0, 2.0]);
+        assert_roots(solve_quadratic(1.0, 0.0, 1.0), &[]);
+        assert_roots(solve_quadratic(0.0, 2.0, -1.0), &[0.5]);
+    }
+
+    #[test]
+    fn quadratic_cancellation() {
+        // Roots 1e-8 and 1e8, the small one is lost by the textbook formula
+        let roots = solve_quadratic(1.0, -(1e8 + 1e-8), 1.0);
+        assert!((roots[0] - 1e-8).abs() < 1e-20);
+        assert!((roots[1] - 1e8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn cubic() {
+        // (x - 1)(x - 2)(x - 3)
+        assert_roots(solve_cubic(1.0, -6.0, 11.0, -6.0), &[1.0, 2.0, 3.0]);
+        // (x - 2)(x^2 + 1)
+        assert_roots(solve_cubic(1.0, -2.0, 1.0, -2.0), &[2.0]);
+    }
+
+    #[test]
+    fn quartic() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(
+            solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        // (x^2 - 1)(x^2 - 4), biquadratic
+        assert_roots(
+            solve_quartic(1.0, 0.0, -5.0, 0.0, 4.0),
+            &[-2.0, -1.0, 1.0, 2.0],
+        );
+        // (x - 1)(x + 3)(x^2 + 1)
+        assert_roots(solve_quartic(2.0, 4.0, -4.0, 4.0, -6.0), &[-3.0, 1.0]);
+        // x^4 + 1
+        assert_roots(solve_quartic(1.0, 0.0, 0.0, 0.0, 1.0), &[]);
+    }
+
+    #[test]
+    fn quartic_spread() {
+        // (x - 0.001)(x - 1)(x - 1000)(x - 10000)
+        let r = [0.001, 1.0, 1000.0, 10000.0];
+        let b = -(r[0] + r[1] + r[2] + r[3]);
+        let c = r[0] * r[1] + r[0] * r[2] + r[0] * r[3] + r[1] * r[2] + r[1] * r[3] + r[2] * r[3];
+        let d =
+            -(r[0] * r[1] * r[2] + r[0] * r[1] * r[3] + r[0] * r[2] * r[3] + r[1] * r[2] * r[3]);
+        let e = r[0] * r[1] * r[2] * r[3];
+        let roots = solve_quartic(1.0, b, c, d, e);
+        assert_eq!(roots.len(), 4);
+        for (a, e) in roots.iter().zip(r) {
+            assert!((a - e).abs() < 1e-9 * e, "{roots:?}");
+        }
+    }
+
+    #[test]
+    fn quartic_double_root() {
+        // (x - 1)^2 (x - 3)(x + 2)
+        let roots = solve_quartic(1.0, -3.0, -3.0, 11.0, -6.0);
+        assert_eq!(roots.len() % 2, 0);
+        assert!((roots[0] + 2.0).abs() < 1e-9);
+        assert!((roots[roots.len() - 1] - 3.0).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,7 @@
 use std::iter::Peekable;
 
+use crate::hit::{Boundary, cross, nearest};
+
 struct UnionStateMachine<I: Iterator> {
     pub iterator: Peekable<I>,
     pub inside: bool,
@@ -7,17 +9,22 @@ struct UnionStateMachine<I: Iterator> {
 
 pub struct RangeDifference<I1, I2>
 where
-    I1: Iterator<Item = f32>,
-    I2: Iterator<Item = f32>,
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
 {
     obj1: UnionStateMachine<I1>,
     obj2: UnionStateMachine<I2>,
+    /// Where the ray last entered `obj1`, whose surface the cut faces take on.
+    entry: Option<I1::Item>,
+    cutter_surface: bool,
 }
 
 impl<I1, I2> RangeDifference<I1, I2>
 where
-    I1: Iterator<Item = f32>,
-    I2: Iterator<Item = f32>,
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
 {
     pub fn new(obj1: I1, obj2: I2) -> Self {
         Self {
@@ -29,46 +36,46 @@ where
                 iterator: obj2.peekable(),
                 inside: false,
             },
+            entry: None,
+            cutter_surface: false,
         }
     }
+
+    /// Keep the surface of `obj2` on the faces it cuts, instead of the surface of `obj1`.
+    pub fn with_cutter_surface(mut self) -> Self {
+        self.cutter_surface = true;
+        self
+    }
 }
 
 impl<I1, I2> Iterator for RangeDifference<I1, I2>
 where
-    I1: Iterator<Item = f32>,
-    I2: Iterator<Item = f32>,
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
 {
-    type Item = f32;
-
-    fn next(&mut self) -> Option<f32> {
-        match (self.obj1.iterator.peek(), self.obj2.iterator.peek()) {
-            (None, None) => None,
-            (None, Some(_)) => None,
-            (Some(_), None) => self.obj1.iterator.next(),
-            (Some(a), Some(b)) => {
-                if a < b {
-                    let applies_before = self.obj1.inside && !self.obj2.inside;
-                    let this = &mut self.obj1;
-                    let v = this.iterator.next();
-                    this.inside = !this.inside;
-                    let applies_after = self.obj1.inside && !self.obj2.inside;
-                    if applies_before != applies_after {
-                        v
-                    } else {
-                        self.next()
-                    }
-                } else {
-                    let applies_before = self.obj1.inside && !self.obj2.inside;
-                    let this = &mut self.obj2;
-                    let v = this.iterator.next();
-                    this.inside = !this.inside;
-                    let applies_after = self.obj1.inside && !self.obj2.inside;
-                    if applies_before != applies_after {
-                        v
-                    } else {
-                        self.next()
-                    }
-                }
+    type Item = I1::Item;
+
+    fn next(&mut self) -> Option<I1::Item> {
+        loop {
+            self.obj1.iterator.peek()?;
+            let (first, second) = nearest(self.obj1.iterator.peek(), self.obj2.iterator.peek());
+            let applies_before = self.obj1.inside && !self.obj2.inside;
+            let v1 = first
+                .then(|| cross(&mut self.obj1.iterator, &mut self.obj1.inside))
+                .flatten();
+            if v1.is_some() && self.obj1.inside {
+                self.entry = v1;
+            }
+            let mut v2 = second
+                .then(|| cross(&mut self.obj2.iterator, &mut self.obj2.inside))
+                .flatten()
+                .map(Boundary::flip);
+            if let (Some(cut), Some(entry), false) = (v2, self.entry, self.cutter_surface) {
+                v2 = Some(cut.with_surface_of(&entry));
+            }
+            if applies_before != (self.obj1.inside && !self.obj2.inside) {
+                return v1.or(v2);
             }
         }
     }
@@ -76,7 +83,13 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::range_difference::RangeDifference;
+    use glam::Vec3;
+
+    use crate::{
+        hit::{Hit, crossings},
+        material::Material,
+        range_difference::RangeDifference,
+    };
 
     #[test]
     fn union_null() {
@@ -129,4 +142,52 @@ mod tests {
         let union: Vec<f32> = RangeDifference::new(obj1.into_iter(), obj2.into_iter()).collect();
         assert_eq!(union, vec![0.0, 1.0]);
     }
+
+    #[test]
+    fn difference_identical() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.0, 1.0];
+        let union: Vec<f32> = RangeDifference::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(union, vec![]);
+
+        let obj1 = vec![0.0, 2.0];
+        let obj2 = vec![0.0, 1.0];
+        let union: Vec<f32> = RangeDifference::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(union, vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn difference_flips_cutter_normals() {
+        let ray = |hits: &[Hit]| crossings::<2>(hits, Vec3::ZERO, Vec3::X);
+        let obj1 = ray(&[Hit::new(0.0, Vec3::NEG_X), Hit::new(1.0, Vec3::X)]);
+        let obj2 = ray(&[Hit::new(0.5, Vec3::NEG_X), Hit::new(2.0, Vec3::X)]);
+        let difference: Vec<Hit> = RangeDifference::new(obj1, obj2).collect();
+        assert_eq!(
+            difference,
+            ray(&[Hit::new(0.0, Vec3::NEG_X), Hit::new(0.5, Vec3::X)]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn difference_cut_surface() {
+        let paint = |hit: Hit, color| Hit {
+            material: Some(Material::new(color)),
+            ..hit
+        };
+        let obj1 = vec![
+            paint(Hit::new(0.0, Vec3::NEG_X), Vec3::X),
+            paint(Hit::new(1.0, Vec3::X), Vec3::X),
+        ];
+        let obj2 = vec![
+            paint(Hit::new(0.5, Vec3::NEG_X), Vec3::Y),
+            paint(Hit::new(2.0, Vec3::X), Vec3::Y),
+        ];
+        let difference: Vec<Hit> =
+            RangeDifference::new(obj1.clone().into_iter(), obj2.clone().into_iter()).collect();
+        assert_eq!(difference[1].material, Some(Material::new(Vec3::X)));
+        let difference: Vec<Hit> = RangeDifference::new(obj1.into_iter(), obj2.into_iter())
+            .with_cutter_surface()
+            .collect();
+        assert_eq!(difference[1].material, Some(Material::new(Vec3::Y)));
+    }
 }
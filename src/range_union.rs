@@ -1,5 +1,7 @@
 use std::iter::Peekable;
 
+use crate::hit::{Boundary, cross, nearest};
+
 struct UnionStateMachine<I: Iterator> {
     pub iterator: Peekable<I>,
     pub inside: bool,
@@ -7,8 +9,9 @@ struct UnionStateMachine<I: Iterator> {
 
 pub struct RangeUnion<I1, I2>
 where
-    I1: Iterator<Item = f32>,
-    I2: Iterator<Item = f32>,
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
 {
     obj1: UnionStateMachine<I1>,
     obj2: UnionStateMachine<I2>,
@@ -16,8 +19,9 @@ where
 
 impl<I1, I2> RangeUnion<I1, I2>
 where
-    I1: Iterator<Item = f32>,
-    I2: Iterator<Item = f32>,
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
 {
     pub fn new(obj1: I1, obj2: I2) -> Self {
         Self {
@@ -35,30 +39,23 @@ where
 
 impl<I1, I2> Iterator for RangeUnion<I1, I2>
 where
-    I1: Iterator<Item = f32>,
-    I2: Iterator<Item = f32>,
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
 {
-    type Item = f32;
+    type Item = I1::Item;
 
-    fn next(&mut self) -> Option<f32> {
-        match (self.obj1.iterator.peek(), self.obj2.iterator.peek()) {
-            (None, None) => None,
-            (None, Some(_)) => self.obj2.iterator.next(),
-            (Some(_), None) => self.obj1.iterator.next(),
-            (Some(a), Some(b)) => {
-                if a < b {
-                    let this = &mut self.obj1;
-                    let other = &mut self.obj2;
-                    let v = this.iterator.next();
-                    this.inside = !this.inside;
-                    if !other.inside { v } else { self.next() }
-                } else {
-                    let this = &mut self.obj2;
-                    let other = &mut self.obj1;
-                    let v = this.iterator.next();
-                    this.inside = !this.inside;
-                    if !other.inside { v } else { self.next() }
-                }
+    fn next(&mut self) -> Option<I1::Item> {
+        loop {
+            let (first, second) = nearest(self.obj1.iterator.peek(), self.obj2.iterator.peek());
+            if !first && !second {
+                return None;
+            }
+            let outside_before = !self.obj1.inside && !self.obj2.inside;
+            let v1 = first.then(|| cross(&mut self.obj1.iterator, &mut self.obj1.inside));
+            let v2 = second.then(|| cross(&mut self.obj2.iterator, &mut self.obj2.inside));
+            if outside_before != (!self.obj1.inside && !self.obj2.inside) {
+                return v1.flatten().or(v2.flatten());
             }
         }
     }
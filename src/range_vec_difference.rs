@@ -0,0 +1,203 @@
+use std::{cmp::Ordering, iter::Peekable};
+
+use crate::{
+    hit::{Boundary, cross},
+    scratch::ScratchVec,
+};
+
+struct DifferenceStateMachine<I: Iterator> {
+    pub iterator: Peekable<I>,
+    pub inside: bool,
+}
+
+/// Subtracts every cutter from the base, counting how many cutters the ray is inside of.
+pub struct RangeVecDifference<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item> + 'static,
+{
+    base: DifferenceStateMachine<I1>,
+    cutters: ScratchVec<DifferenceStateMachine<I2>>,
+    count: usize,
+    /// Where the ray last entered the base, whose surface the cut faces take on.
+    entry: Option<I1::Item>,
+    cutter_surface: bool,
+}
+
+impl<I1, I2> RangeVecDifference<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item> + 'static,
+{
+    fn applies(&self) -> bool {
+        self.base.inside && self.count == 0
+    }
+}
+
+impl<I1, I2> Iterator for RangeVecDifference<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item> + 'static,
+{
+    type Item = I1::Item;
+
+    fn next(&mut self) -> Option<I1::Item> {
+        loop {
+            let base = self.base.iterator.peek()?.t();
+            let applies_before = self.applies();
+            let cutter = self
+                .cutters
+                .iter_mut()
+                .filter_map(|sm| sm.iterator.peek().map(Boundary::t))
+                .min_by(|va, vb| va.partial_cmp(vb).unwrap_or(Ordering::Less));
+            // The base crosses unless a cutter comes first, along with every cutter at
+            // the same distance
+            let (crosses_base, t) = match cutter {
+                Some(cutter) if cutter.partial_cmp(&base) == Some(Ordering::Less) => {
+                    (false, cutter)
+                }
+                _ => (true, base),
+            };
+            let v = crosses_base
+                .then(|| cross(&mut self.base.iterator, &mut self.base.inside))
+                .flatten();
+            if v.is_some() && self.base.inside {
+                self.entry = v;
+            }
+            let mut cut = None;
+            for sm in self.cutters.iter_mut() {
+                if sm.iterator.peek().is_none_or(|b| b.t() != t) {
+                    continue;
+                }
+                let inside_before = sm.inside;
+                let crossed = cross(&mut sm.iterator, &mut sm.inside);
+                if sm.inside != inside_before {
+                    if sm.inside {
+                        self.count += 1;
+                    } else {
+                        self.count -= 1;
+                    }
+                }
+                cut = cut.or(crossed);
+            }
+            if applies_before != self.applies() {
+                let mut cut = cut.map(Boundary::flip);
+                if let (Some(c), Some(entry), false) = (cut, self.entry, self.cutter_surface) {
+                    cut = Some(c.with_surface_of(&entry));
+                }
+                return v.or(cut);
+            }
+        }
+    }
+}
+
+impl<I1, I2> RangeVecDifference<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item> + 'static,
+{
+    pub fn new(base: I1, cutters: impl IntoIterator<Item = I2>) -> Self {
+        let mut machines = ScratchVec::new();
+        machines.extend(cutters.into_iter().map(|v| DifferenceStateMachine {
+            iterator: v.peekable(),
+            inside: false,
+        }));
+        Self {
+            base: DifferenceStateMachine {
+                iterator: base.peekable(),
+                inside: false,
+            },
+            cutters: machines,
+            count: 0,
+            entry: None,
+            cutter_surface: false,
+        }
+    }
+
+    /// Keep the surface of the cutters on the faces they cut, instead of the surface
+    /// of the base.
+    pub fn with_cutter_surface(mut self) -> Self {
+        self.cutter_surface = true;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::range_vec_difference::RangeVecDifference;
+
+    #[test]
+    fn union_null() {
+        let obj1 = vec![];
+        let obj2 = vec![];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![]);
+    }
+
+    #[test]
+    fn union_single() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.0, 1.0]);
+
+        let obj1 = vec![];
+        let obj2 = vec![0.0, 1.0];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![]);
+    }
+
+    #[test]
+    fn union_intersect_once() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.5, 2.0];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.0, 0.5]);
+    }
+
+    #[test]
+    fn union_contain() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.5, 0.7];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.0, 0.5, 0.7, 1.0]);
+    }
+
+    #[test]
+    fn union_multiple() {
+        let obj1 = vec![0.0, 1.0, 2.0, 3.0];
+        let obj2 = vec![0.5, 2.5];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn union_touching() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![1.0, 2.0];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn difference_overlapping_cutters() {
+        let obj1 = vec![0.0, 10.0];
+        let obj2 = vec![1.0, 3.0, 6.0, 7.0];
+        let obj3 = vec![2.0, 4.0];
+        let union: Vec<f32> =
+            RangeVecDifference::new(obj1.into_iter(), vec![obj2.into_iter(), obj3.into_iter()])
+                .collect();
+        assert_eq!(union, vec![0.0, 1.0, 4.0, 6.0, 7.0, 10.0]);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{cmp::Ordering, iter::Peekable};
+
+use crate::{
+    hit::{Boundary, cross},
+    scratch::ScratchVec,
+};
+
+struct IntersectStateMachine<I: Iterator> {
+    pub iterator: Peekable<I>,
+    pub inside: bool,
+}
+pub struct RangeVecIntersect<I>
+where
+    I: Iterator + 'static,
+    I::Item: Boundary,
+{
+    machines: ScratchVec<IntersectStateMachine<I>>,
+    count: usize,
+}
+
+impl<I> Iterator for RangeVecIntersect<I>
+where
+    I: Iterator + 'static,
+    I::Item: Boundary,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let all = self.machines.len();
+        loop {
+            let count_before = self.count;
+            let (index, t) = self
+                .machines
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, sm)| sm.iterator.peek().map(|b| (i, b.t())))
+                .min_by(|(_, va), (_, vb)| va.partial_cmp(vb).unwrap_or(Ordering::Less))?;
+            // Every operand with a boundary at the same distance crosses it at once
+            let mut v = None;
+            for (i, sm) in self.machines.iter_mut().enumerate() {
+                if i != index && sm.iterator.peek().is_none_or(|b| b.t() != t) {
+                    continue;
+                }
+                let inside_before = sm.inside;
+                let crossed = cross(&mut sm.iterator, &mut sm.inside);
+                if sm.inside != inside_before {
+                    if sm.inside {
+                        self.count += 1;
+                    } else {
+                        self.count -= 1;
+                    }
+                }
+                v = v.or(crossed);
+            }
+            if (count_before == all) != (self.count == all) {
+                return v;
+            }
+        }
+    }
+}
+
+impl<I> RangeVecIntersect<I>
+where
+    I: Iterator + 'static,
+    I::Item: Boundary,
+{
+    pub fn new(objects: impl IntoIterator<Item = I>) -> Self {
+        let mut machines = ScratchVec::new();
+        machines.extend(objects.into_iter().map(|v| IntersectStateMachine {
+            iterator: v.peekable(),
+            inside: false,
+        }));
+        Self { machines, count: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::range_vec_intersect::RangeVecIntersect;
+
+    #[test]
+    fn union_null() {
+        let obj1 = vec![];
+        let obj2 = vec![];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter()]).collect();
+        assert_eq!(union, vec![]);
+    }
+
+    #[test]
+    fn union_single() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter()]).collect();
+        assert_eq!(union, vec![]);
+
+        let obj1 = vec![];
+        let obj2 = vec![0.0, 1.0];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter()]).collect();
+        assert_eq!(union, vec![]);
+    }
+
+    #[test]
+    fn union_intersect_once() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.5, 2.0];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.5, 1.0]);
+    }
+
+    #[test]
+    fn union_contain() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.5, 0.7];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.5, 0.7]);
+    }
+
+    #[test]
+    fn union_multiple() {
+        let obj1 = vec![0.0, 1.0, 2.0, 3.0];
+        let obj2 = vec![0.5, 2.5];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter()]).collect();
+        assert_eq!(union, vec![0.5, 1.0, 2.0, 2.5]);
+    }
+
+    #[test]
+    fn intersect_three() {
+        let obj1 = vec![0.0, 3.0];
+        let obj2 = vec![1.0, 4.0];
+        let obj3 = vec![-1.0, 1.5, 2.0, 5.0];
+        let union: Vec<f32> =
+            RangeVecIntersect::new(vec![obj1.into_iter(), obj2.into_iter(), obj3.into_iter()])
+                .collect();
+        assert_eq!(union, vec![1.0, 1.5, 2.0, 3.0]);
+    }
+}
@@ -1,4 +1,9 @@
-use std::iter::Peekable;
+use std::{cmp::Ordering, iter::Peekable};
+
+use crate::{
+    hit::{Boundary, cross},
+    scratch::ScratchVec,
+};
 
 struct UnionStateMachine<I: Iterator> {
     pub iterator: Peekable<I>,
@@ -6,55 +11,73 @@ struct UnionStateMachine<I: Iterator> {
 }
 pub struct RangeVecUnion<I>
 where
-    I: Iterator<Item = f32>,
+    I: Iterator + 'static,
+    I::Item: Boundary,
 {
-    machines: Vec<UnionStateMachine<I>>,
+    machines: ScratchVec<UnionStateMachine<I>>,
     count: usize,
 }
 
 impl<I> Iterator for RangeVecUnion<I>
 where
-    I: Iterator<Item = f32>,
+    I: Iterator + 'static,
+    I::Item: Boundary,
 {
-    type Item = f32;
+    type Item = I::Item;
 
-    fn next(&mut self) -> Option<f32> {
-        let count_before = self.count;
-        let (sm, _) = self
-            .machines
-            .iter_mut()
-            .filter_map(|sm| sm.iterator.peek().map(ToOwned::to_owned).map(|v| (sm, v)))
-            .min_by(|(_, va), (_, vb)| va.partial_cmp(vb).unwrap_or(std::cmp::Ordering::Less))?;
-        let v = sm.iterator.next().unwrap();
-        if sm.inside {
-            self.count -= 1;
-        } else {
-            self.count += 1;
-        }
-        sm.inside = !sm.inside;
-        if count_before == 0 || self.count == 0 {
-            Some(v)
-        } else {
-            self.next()
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let count_before = self.count;
+            let (index, t) = self
+                .machines
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, sm)| sm.iterator.peek().map(|b| (i, b.t())))
+                .min_by(|(_, va), (_, vb)| va.partial_cmp(vb).unwrap_or(Ordering::Less))?;
+            // Every operand with a boundary at the same distance crosses it at once
+            let mut v = None;
+            for (i, sm) in self.machines.iter_mut().enumerate() {
+                if i != index && sm.iterator.peek().is_none_or(|b| b.t() != t) {
+                    continue;
+                }
+                let inside_before = sm.inside;
+                let crossed = cross(&mut sm.iterator, &mut sm.inside);
+                if sm.inside != inside_before {
+                    if sm.inside {
+                        self.count += 1;
+                    } else {
+                        self.count -= 1;
+                    }
+                }
+                v = v.or(crossed);
+            }
+            if (count_before == 0) != (self.count == 0) {
+                return v;
+            }
         }
     }
 }
 
 impl<I> RangeVecUnion<I>
 where
-    I: Iterator<Item = f32>,
+    I: Iterator + 'static,
+    I::Item: Boundary,
 {
-    pub fn new(objects: Vec<I>) -> Self {
-        Self {
-            machines: objects
-                .into_iter()
-                .map(|v| UnionStateMachine {
-                    iterator: v.peekable(),
-                    inside: false,
-                })
-                .collect(),
+    pub fn new(objects: impl IntoIterator<Item = I>) -> Self {
+        let mut union = Self {
+            machines: ScratchVec::new(),
             count: 0,
-        }
+        };
+        objects.into_iter().for_each(|object| union.push(object));
+        union
+    }
+
+    /// Adds another operand, only valid before the first call to `next`.
+    pub fn push(&mut self, object: I) {
+        self.machines.push(UnionStateMachine {
+            iterator: object.peekable(),
+            inside: false,
+        });
     }
 }
 
@@ -0,0 +1,136 @@
+use std::iter::Peekable;
+
+use crate::hit::{Boundary, cross, nearest};
+
+struct XorStateMachine<I: Iterator> {
+    pub iterator: Peekable<I>,
+    pub inside: bool,
+}
+
+/// Symmetric difference, the parts inside exactly one of the two operands. Every
+/// boundary of either operand is one of the result, seen from the other side while
+/// inside the other operand.
+pub struct RangeXor<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
+{
+    obj1: XorStateMachine<I1>,
+    obj2: XorStateMachine<I2>,
+}
+
+impl<I1, I2> RangeXor<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
+{
+    pub fn new(obj1: I1, obj2: I2) -> Self {
+        Self {
+            obj1: XorStateMachine {
+                iterator: obj1.peekable(),
+                inside: false,
+            },
+            obj2: XorStateMachine {
+                iterator: obj2.peekable(),
+                inside: false,
+            },
+        }
+    }
+}
+
+impl<I1, I2> Iterator for RangeXor<I1, I2>
+where
+    I1: Iterator,
+    I1::Item: Boundary,
+    I2: Iterator<Item = I1::Item>,
+{
+    type Item = I1::Item;
+
+    fn next(&mut self) -> Option<I1::Item> {
+        loop {
+            let (first, second) = nearest(self.obj1.iterator.peek(), self.obj2.iterator.peek());
+            if !first && !second {
+                return None;
+            }
+            let v1 = first
+                .then(|| cross(&mut self.obj1.iterator, &mut self.obj1.inside))
+                .flatten();
+            let v2 = second
+                .then(|| cross(&mut self.obj2.iterator, &mut self.obj2.inside))
+                .flatten();
+            // Where both cross at once, the ray stays inside exactly one or neither
+            match (v1, v2) {
+                (Some(v), None) => return Some(if self.obj2.inside { v.flip() } else { v }),
+                (None, Some(v)) => return Some(if self.obj1.inside { v.flip() } else { v }),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::range_xor::RangeXor;
+
+    #[test]
+    fn xor_null() {
+        let obj1 = vec![];
+        let obj2 = vec![];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![]);
+    }
+
+    #[test]
+    fn xor_single() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn xor_overlap() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.5, 2.0];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![0.0, 0.5, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn xor_contain() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.5, 0.7];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![0.0, 0.5, 0.7, 1.0]);
+    }
+
+    #[test]
+    fn xor_identical() {
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![0.0, 1.0];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![]);
+
+        let obj1 = vec![0.0, 1.0, 2.0, 3.0];
+        let obj2 = vec![0.0, 1.0];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn xor_shared_boundary() {
+        // Touching, the two intervals merge into one
+        let obj1 = vec![0.0, 1.0];
+        let obj2 = vec![1.0, 2.0];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![0.0, 2.0]);
+
+        // Sharing an end, only the part inside just one operand is left
+        let obj1 = vec![0.0, 2.0];
+        let obj2 = vec![1.0, 2.0];
+        let xor: Vec<f32> = RangeXor::new(obj1.into_iter(), obj2.into_iter()).collect();
+        assert_eq!(xor, vec![0.0, 1.0]);
+    }
+}
@@ -0,0 +1,475 @@
+use glam::Vec3;
+use image::{ImageBuffer, Luma, RgbImage};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    camera::{Camera, Ray},
+    hit::{Hit, crossings},
+    objects::Object,
+    range_intersect::RangeIntersect,
+    scene::Scene,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Each surface's material lit by the scene's lights.
+    Shaded,
+    /// World space normals mapped from -1..1 to the 0..255 color range.
+    Normals,
+}
+
+/// Where the samples of a supersampled pixel are placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sampling {
+    /// Centers of an N×N grid of sub-pixels.
+    Grid,
+    /// A random point inside each of the N×N sub-pixels.
+    Jitter,
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub height: u32,
+    pub mode: RenderMode,
+    /// Pixels are sampled `samples`×`samples` times.
+    pub samples: u32,
+    pub sampling: Sampling,
+    /// Only supersample pixels whose neighbors disagree on hit/miss or depth.
+    pub adaptive: bool,
+    /// Trace shadow rays towards the lights.
+    pub shadows: bool,
+    /// Depth stored as `u16::MAX` in the depth pass, the farthest visible point when `None`.
+    pub depth_far: Option<f32>,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 1024,
+            height: 1024,
+            mode: RenderMode::Shaded,
+            samples: 1,
+            sampling: Sampling::Grid,
+            adaptive: false,
+            shadows: true,
+            depth_far: None,
+        }
+    }
+}
+
+/// Shadow rays ignore intervals ending this close to their origin, which would
+/// otherwise be the surface the ray starts from.
+const SHADOW_EPSILON: f32 = 1e-4;
+
+/// Relative depth difference between neighbors that triggers adaptive supersampling.
+const ADAPTIVE_DEPTH_THRESHOLD: f32 = 0.05;
+
+#[derive(Clone, Copy)]
+struct Sample {
+    /// Linear color.
+    color: Vec3,
+    /// First boundary in front of the camera, with what the depth pass stores for it.
+    hit: Option<(f32, Hit)>,
+}
+
+struct Renderer<'a> {
+    scene: &'a Scene,
+    settings: &'a RenderSettings,
+    camera: Box<dyn Camera>,
+    aspect_ratio: f32,
+}
+
+/// Cheap integer hash (lowbias32), used to jitter samples reproducibly.
+fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846ca68b);
+    x ^= x >> 16;
+    x
+}
+
+fn random(x: u32, y: u32, i: u32) -> f32 {
+    let h = hash(x ^ hash(y ^ hash(i)));
+    (h >> 8) as f32 / (1 << 24) as f32
+}
+
+impl<'a> Renderer<'a> {
+    fn new(scene: &'a Scene, settings: &'a RenderSettings) -> Self {
+        Self {
+            scene,
+            settings,
+            camera: scene.camera.camera(),
+            aspect_ratio: settings.width as f32 / settings.height as f32,
+        }
+    }
+
+    /// Whether anything lies between `point` and `light`.
+    fn occluded(&self, point: Vec3, light: Vec3) -> bool {
+        let direction = light - point;
+        let distance = direction.length();
+        let direction = direction / distance;
+        let mut i = self.scene.object.trace(point, direction);
+        while let (Some(entry), Some(exit)) = (i.next(), i.next()) {
+            if entry.t >= distance {
+                return false;
+            }
+            if exit.t > SHADOW_EPSILON {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Primary ray through the point `(x, y)` of the image, in pixels.
+    fn primary(&self, x: f32, y: f32) -> Option<Ray> {
+        let x = x / self.settings.width as f32;
+        let y = y / self.settings.height as f32;
+        self.camera
+            .ray((x - 0.5) * 2.0, (0.5 - y) * 2.0, self.aspect_ratio)
+    }
+
+    /// First boundary in front of the camera.
+    fn first_hit(&self, ray: &Ray) -> Option<Hit> {
+        let i = self.scene.object.trace(ray.origin, ray.direction);
+        let mut i = RangeIntersect::new(
+            i,
+            crossings::<2>(
+                &[
+                    Hit::new(0.0, Vec3::ZERO),
+                    Hit::new(f32::INFINITY, Vec3::ZERO),
+                ],
+                ray.origin,
+                ray.direction,
+            ),
+        );
+        i.next()
+    }
+
+    /// Traces a ray through the point `(x, y)` of the image, in pixels.
+    fn sample(&self, x: f32, y: f32) -> Sample {
+        let background = Sample {
+            color: Vec3::ZERO,
+            hit: None,
+        };
+        let Some(ray) = self.primary(x, y) else {
+            return background;
+        };
+        let direction = ray.direction;
+        let hit = match self.first_hit(&ray) {
+            Some(hit) => hit,
+            None => return background,
+        };
+        let color = match self.settings.mode {
+            RenderMode::Shaded => {
+                let material = hit.material.unwrap_or_default();
+                let v = direction * hit.t + ray.origin;
+                let lit: Vec3 = self
+                    .scene
+                    .lights
+                    .iter()
+                    .map(|&light| {
+                        let to_light = (light - v).normalize();
+                        let diffuse = to_light.dot(hit.normal).clamp(0.0, 1.0);
+                        if diffuse == 0.0 || self.settings.shadows && self.occluded(v, light) {
+                            return Vec3::ZERO;
+                        }
+                        // Blinn-Phong highlight
+                        let half = (to_light - direction).normalize();
+                        let specular = material.specular
+                            * half.dot(hit.normal).max(0.0).powf(material.shininess());
+                        material.color * diffuse + Vec3::splat(specular)
+                    })
+                    .sum();
+                material.emission + lit + material.color * 0.01
+            }
+            RenderMode::Normals => hit.normal * 0.5 + 0.5,
+        };
+        Sample {
+            color,
+            hit: Some((self.camera.depth(&ray, hit.t), hit)),
+        }
+    }
+
+    /// Average linear color of the `samples`×`samples` samples of a pixel.
+    fn supersample(&self, x: u32, y: u32) -> Vec3 {
+        let n = self.settings.samples;
+        let mut color = Vec3::ZERO;
+        for i in 0..n * n {
+            let (ox, oy) = match self.settings.sampling {
+                Sampling::Grid => (0.5, 0.5),
+                Sampling::Jitter => (random(x, y, 2 * i), random(x, y, 2 * i + 1)),
+            };
+            let sx = x as f32 + ((i % n) as f32 + ox) / n as f32;
+            let sy = y as f32 + ((i / n) as f32 + oy) / n as f32;
+            color += self.sample(sx, sy).color;
+        }
+        color / (n * n) as f32
+    }
+
+    fn to_rgb(&self, color: Vec3) -> image::Rgb<u8> {
+        let color = match self.settings.mode {
+            RenderMode::Shaded => color.powf(1.0 / 2.2),
+            RenderMode::Normals => color,
+        };
+        quantize(color)
+    }
+}
+
+fn quantize(color: Vec3) -> image::Rgb<u8> {
+    image::Rgb([
+        (color.x.clamp(0.0, 1.0) * 255.0) as u8,
+        (color.y.clamp(0.0, 1.0) * 255.0) as u8,
+        (color.z.clamp(0.0, 1.0) * 255.0) as u8,
+    ])
+}
+
+fn disagree(a: Sample, b: Sample) -> bool {
+    match (a.hit, b.hit) {
+        (Some((a, _)), Some((b, _))) => (a - b).abs() > ADAPTIVE_DEPTH_THRESHOLD * a.min(b),
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+pub fn render(scene: &Scene, settings: &RenderSettings) -> RgbImage {
+    trace_image(&Renderer::new(scene, settings), false).0
+}
+
+/// Traces the image, along with the samples through the pixel centers when adaptive
+/// supersampling needs them or `keep_centers` is set.
+fn trace_image(renderer: &Renderer, keep_centers: bool) -> (RgbImage, Vec<Sample>) {
+    let settings = renderer.settings;
+    let (width, height) = (settings.width, settings.height);
+    let adaptive = settings.adaptive && settings.samples > 1;
+
+    let mut img = RgbImage::new(width, height);
+    if !adaptive && !keep_centers {
+        img.par_enumerate_pixels_mut().for_each(|(x, y, p)| {
+            *p = renderer.to_rgb(renderer.supersample(x, y));
+        });
+        return (img, vec![]);
+    }
+
+    // A single grid sample is the center itself, so it needn't be traced again
+    let center_only = settings.samples <= 1 && settings.sampling == Sampling::Grid;
+    let centers: Vec<Sample> = (0..width * height)
+        .into_par_iter()
+        .map(|i| renderer.sample((i % width) as f32 + 0.5, (i / width) as f32 + 0.5))
+        .collect();
+    img.par_enumerate_pixels_mut().for_each(|(x, y, p)| {
+        let center = centers[(y * width + x) as usize];
+        let edge = || {
+            let neighbors = [
+                (x > 0).then(|| (x - 1, y)),
+                (x + 1 < width).then(|| (x + 1, y)),
+                (y > 0).then(|| (x, y - 1)),
+                (y + 1 < height).then(|| (x, y + 1)),
+            ];
+            neighbors
+                .into_iter()
+                .flatten()
+                .any(|(nx, ny)| disagree(center, centers[(ny * width + nx) as usize]))
+        };
+        let color = if center_only || adaptive && !edge() {
+            center.color
+        } else {
+            renderer.supersample(x, y)
+        };
+        *p = renderer.to_rgb(color);
+    });
+    (img, centers)
+}
+
+pub type Gray16Image = ImageBuffer<Luma<u16>, Vec<u16>>;
+
+/// Arbitrary output variables, all taken from the ray through each pixel's center.
+pub struct Aovs {
+    /// Distance along the viewing direction, `RenderSettings::depth_far` and
+    /// background pixels are stored as `u16::MAX`.
+    pub depth: Gray16Image,
+    /// World space normals mapped from -1..1 to 0..255, background pixels are black.
+    pub normals: RgbImage,
+    /// ID of the primitive seen through each pixel, 0 for the background. `None` when
+    /// the scene has more primitives than 16 bits tell apart.
+    pub ids: Option<Gray16Image>,
+}
+
+/// Renders the image like `render`, along with the passes, which are taken from the
+/// hits of the same trace.
+pub fn render_with_aovs(scene: &Scene, settings: &RenderSettings) -> (RgbImage, Aovs) {
+    let (width, height) = (settings.width, settings.height);
+    let (image, centers) = trace_image(&Renderer::new(scene, settings), true);
+    let depth_far = settings.depth_far.unwrap_or_else(|| {
+        centers
+            .iter()
+            .flat_map(|sample| sample.hit)
+            .map(|(depth, _)| depth)
+            .fold(0.0, f32::max)
+    });
+    let hit = |x: u32, y: u32| centers[(y * width + x) as usize].hit;
+    let aovs = Aovs {
+        depth: ImageBuffer::from_fn(width, height, |x, y| {
+            let depth = hit(x, y).map_or(1.0, |(depth, _)| depth / depth_far);
+            Luma([(depth.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16])
+        }),
+        normals: RgbImage::from_fn(width, height, |x, y| match hit(x, y) {
+            Some((_, hit)) => quantize(hit.normal * 0.5 + 0.5),
+            None => image::Rgb([0, 0, 0]),
+        }),
+        ids: (scene.primitives <= u16::MAX as u32).then(|| {
+            ImageBuffer::from_fn(width, height, |x, y| {
+                Luma([hit(x, y).map_or(0, |(_, hit)| hit.id as u16)])
+            })
+        }),
+    };
+    (image, aovs)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::vec3;
+
+    use crate::{
+        render::{
+            RenderMode, RenderSettings, Renderer, Sampling, disagree, quantize, render,
+            render_with_aovs,
+        },
+        scene::Scene,
+    };
+
+    fn scene() -> Scene {
+        Scene::parse(
+            "object union { sphere 0 0 0 1  box -0.5 0.5 -0.5 0.5 1.5 0.5 }",
+            "test",
+        )
+        .unwrap()
+    }
+
+    fn settings(samples: u32, sampling: Sampling, adaptive: bool) -> RenderSettings {
+        RenderSettings {
+            width: 24,
+            height: 24,
+            mode: RenderMode::Normals,
+            samples,
+            sampling,
+            adaptive,
+            ..RenderSettings::default()
+        }
+    }
+
+    #[test]
+    fn single_sample_is_the_center() {
+        let scene = scene();
+        let settings = settings(1, Sampling::Grid, false);
+        let renderer = Renderer::new(&scene, &settings);
+        for (x, y, p) in render(&scene, &settings).enumerate_pixels() {
+            let center = renderer.sample(x as f32 + 0.5, y as f32 + 0.5);
+            assert_eq!(*p, renderer.to_rgb(center.color), "{x} {y}");
+        }
+    }
+
+    #[test]
+    fn adaptive_supersamples_edges_only() {
+        let scene = scene();
+        let adaptive = settings(3, Sampling::Grid, true);
+        let full = render(&scene, &settings(3, Sampling::Grid, false));
+        let image = render(&scene, &adaptive);
+        let renderer = Renderer::new(&scene, &adaptive);
+        let center = |x: u32, y: u32| renderer.sample(x as f32 + 0.5, y as f32 + 0.5);
+        let (mut flat, mut edges) = (0, 0);
+        for (x, y, p) in image.enumerate_pixels() {
+            let edge = [(-1, 0), (1, 0), (0, -1), (0, 1)].iter().any(|&(dx, dy)| {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                (0..24).contains(&nx)
+                    && (0..24).contains(&ny)
+                    && disagree(center(x, y), center(nx as u32, ny as u32))
+            });
+            if edge {
+                assert_eq!(p, full.get_pixel(x, y), "{x} {y}");
+                edges += 1;
+            } else {
+                assert_eq!(*p, renderer.to_rgb(center(x, y).color), "{x} {y}");
+                flat += 1;
+            }
+        }
+        assert!(edges > 20 && flat > 200, "{edges} {flat}");
+        // The silhouette pixels did get more than one sample
+        let single = render(&scene, &settings(1, Sampling::Grid, false));
+        assert_ne!(image, single);
+    }
+
+    #[test]
+    fn jitter_is_reproducible() {
+        let scene = scene();
+        let jitter = settings(2, Sampling::Jitter, false);
+        let image = render(&scene, &jitter);
+        assert_eq!(image, render(&scene, &jitter));
+        assert_ne!(image, render(&scene, &settings(2, Sampling::Grid, false)));
+    }
+
+    #[test]
+    fn aovs_share_the_trace() {
+        let scene = scene();
+        for settings in [
+            settings(1, Sampling::Grid, false),
+            settings(3, Sampling::Grid, true),
+            settings(2, Sampling::Jitter, false),
+        ] {
+            let (image, aovs) = render_with_aovs(&scene, &settings);
+            assert_eq!(image, render(&scene, &settings));
+            let ids = aovs.ids.unwrap();
+            let renderer = Renderer::new(&scene, &settings);
+            let mut hits = 0;
+            for (x, y, p) in aovs.normals.enumerate_pixels() {
+                let center = renderer.sample(x as f32 + 0.5, y as f32 + 0.5);
+                match center.hit {
+                    Some((_, hit)) => {
+                        assert_eq!(*p, quantize(hit.normal * 0.5 + 0.5), "{x} {y}");
+                        assert_eq!(ids.get_pixel(x, y).0, [hit.id as u16], "{x} {y}");
+                        hits += 1;
+                    }
+                    None => {
+                        assert_eq!(p.0, [0, 0, 0], "{x} {y}");
+                        assert_eq!(ids.get_pixel(x, y).0, [0], "{x} {y}");
+                        assert_eq!(aovs.depth.get_pixel(x, y).0, [u16::MAX], "{x} {y}");
+                    }
+                }
+            }
+            assert!(hits > 100, "{hits}");
+            // Both primitives are seen
+            assert!(ids.pixels().any(|p| p.0 == [1]) && ids.pixels().any(|p| p.0 == [2]));
+            assert_eq!(ids.pixels().filter(|p| p.0 == [0]).count(), 576 - hits);
+        }
+    }
+
+    #[test]
+    fn too_many_ids() {
+        let mut scene = scene();
+        scene.primitives = u16::MAX as u32 + 1;
+        let (_, aovs) = render_with_aovs(&scene, &settings(1, Sampling::Grid, false));
+        assert!(aovs.ids.is_none());
+    }
+
+    #[test]
+    fn occluders() {
+        // A floor with its top at y = 0 and a ball hovering above the origin
+        let scene = Scene::parse(
+            "object union { box -5 -1 -5 5 0 5  sphere 0 2 0 0.5 }",
+            "test",
+        )
+        .unwrap();
+        let settings = RenderSettings::default();
+        let renderer = Renderer::new(&scene, &settings);
+        assert!(renderer.occluded(vec3(0.0, 0.0, 0.0), vec3(0.0, 5.0, 0.0)));
+        assert!(renderer.occluded(vec3(0.2, 0.0, 0.0), vec3(-0.2, 5.0, 0.0)));
+        // The ball lies beyond the light
+        assert!(!renderer.occluded(vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)));
+        // Nothing in between, and the floor the ray starts on doesn't count
+        assert!(!renderer.occluded(vec3(3.0, 0.0, 0.0), vec3(3.0, 5.0, 0.0)));
+        assert!(!renderer.occluded(vec3(3.0, 0.0, 0.0), vec3(0.0, 5.0, 3.0)));
+        // Even when rounding put the point just below the surface
+        assert!(!renderer.occluded(vec3(3.0, -1e-5, 0.0), vec3(3.0, 5.0, 0.0)));
+    }
+}
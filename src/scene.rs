@@ -0,0 +1,736 @@
+//! Loader for `.scene` files.
+//!
+//! A scene is a list of whitespace separated tokens, `#` starts a comment:
+//!
+//! ```text
+//! camera { eye 3 3 0  target 0 0 0  up 0 1 0  fov 53.13 }
+//! light 200 200 -200
+//! define hole transform rotate_x 90 translate 0 0.5 0 { cylinder 0.2 1 }
+//! object difference { sphere 0 0 0 1  use hole }
+//! ```
+//!
+//! Nodes are `sphere cx cy cz r`, `cylinder r h`, `box x0 y0 z0 x1 y1 z1 [round r]`,
+//! `cone r0 r1 h`, `torus R r`, `halfspace nx ny nz d`,
+//! `union|intersect|difference|xor { a b }`, `vec_union|vec_intersect { ... }`,
+//! `vec_difference { base cutters... }`, `complement { a }`, `clipplane nx ny nz d { a }`,
+//! `transform ops { a }`, `material properties { a }` and `use name`. A `halfspace` is
+//! everything behind the plane, and `complement` everything outside its child, so
+//! `intersect { box ...  complement { sphere ... } }` carves a sphere out of a box.
+//! Transform ops (`translate x y z`, `rotate_x|rotate_y|rotate_z deg`, `scale s` or
+//! `scale x y z`) apply to the child in the order they are written, a scale must not be
+//! zero along any axis.
+//! Material properties are `color r g b`, `roughness x`, `specular x` and `emission r g b`,
+//! anything left out keeps its default. The faces cut by a `difference` or `vec_difference`
+//! keep the material of the base, unless written as `difference cutter_material { a b }`.
+//!
+//! Besides `eye`, `target`, `up` and `fov`, the camera takes
+//! `projection perspective|orthographic|fisheye|equirectangular` and, for orthographic
+//! views, the `height` of the scene the image covers. A perspective `fov` must be below 180.
+//!
+//! Every primitive is tagged with an ID, counting from 1 in the order the primitives
+//! appear, with each `use` of a definition counting as a fresh copy.
+
+use std::{collections::HashMap, fmt, path::Path};
+
+use glam::{Affine3A, Vec3, vec3};
+
+use crate::{
+    camera::{CameraSettings, Projection},
+    material::Material,
+    objects::{
+        Object, bounded::CSGBounded, clipplane::CSGClipplane, complement::CSGComplement,
+        cone::CSGCone, cuboid::CSGBox, cylinder::CSGCylinder, difference::CSGDifference,
+        dynamic::DynObject, halfspace::CSGHalfSpace, intersect::CSGIntersect,
+        material::CSGMaterial, sphere::CSGSphere, tag::CSGTag, torus::CSGTorus,
+        transform::CSGTransform, union::CSGUnion, vec_difference::CSGVecDifference,
+        vec_intersect::CSGVecIntersect, vec_union::CSGVecUnion, xor::CSGXor,
+    },
+};
+
+pub struct Scene {
+    pub object: DynObject,
+    pub camera: CameraSettings,
+    pub lights: Vec<Vec3>,
+    /// Number of primitives, which is also the largest ID any of them carries.
+    pub primitives: u32,
+}
+
+#[derive(Debug)]
+pub struct SceneError {
+    pub file: String,
+    /// 1-based, zero when the error is not tied to a position (e.g. the file is missing).
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}: {}", self.file, self.message);
+        }
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file, self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    for (line_index, line) in source.lines().enumerate() {
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let mut start = None;
+        for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+            if c.is_whitespace() || c == '{' || c == '}' {
+                if let Some(s) = start.take() {
+                    tokens.push(Token {
+                        text: &line[s..i],
+                        line: line_index + 1,
+                        column: s + 1,
+                    });
+                }
+                if c == '{' || c == '}' {
+                    tokens.push(Token {
+                        text: &line[i..i + 1],
+                        line: line_index + 1,
+                        column: i + 1,
+                    });
+                }
+            } else if start.is_none() {
+                start = Some(i);
+            }
+        }
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    file: &'a str,
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    /// Token position of each defined node, which is parsed again on every `use`
+    /// so each instance gets primitive IDs of its own.
+    defines: HashMap<&'a str, usize>,
+    next_id: u32,
+}
+
+impl<'a> Parser<'a> {
+    fn error_at(&self, position: usize, message: String) -> SceneError {
+        let (line, column) = match self.tokens.get(position).or(self.tokens.last()) {
+            Some(token) => (token.line, token.column),
+            None => (1, 1),
+        };
+        SceneError {
+            file: self.file.to_owned(),
+            line,
+            column,
+            message,
+        }
+    }
+
+    fn error(&self, message: String) -> SceneError {
+        self.error_at(self.position, message)
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(|t| t.text)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<&'a str, SceneError> {
+        let token = self
+            .peek()
+            .ok_or_else(|| self.error(format!("expected {expected}, found end of file")))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), SceneError> {
+        let token = self.next(&format!("`{expected}`"))?;
+        if token != expected {
+            self.position -= 1;
+            return Err(self.error(format!("expected `{expected}`, found `{token}`")));
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<f32, SceneError> {
+        let token = self.next("a number")?;
+        token.parse().map_err(|_| {
+            self.error_at(
+                self.position - 1,
+                format!("expected a number, found `{token}`"),
+            )
+        })
+    }
+
+    fn is_number(&self) -> bool {
+        self.peek().is_some_and(|t| t.parse::<f32>().is_ok())
+    }
+
+    fn vector(&mut self) -> Result<Vec3, SceneError> {
+        Ok(vec3(self.number()?, self.number()?, self.number()?))
+    }
+
+    fn positive(&mut self, what: &str) -> Result<f32, SceneError> {
+        let value = self.number()?;
+        if value <= 0.0 {
+            return Err(self.error_at(
+                self.position - 1,
+                format!("{what} must be positive, found {value}"),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn children(&mut self, node: &str, count: Option<usize>) -> Result<Vec<DynObject>, SceneError> {
+        let start = self.position;
+        self.expect("{")?;
+        let mut children = vec![];
+        while self.peek() != Some("}") {
+            children.push(self.node()?);
+        }
+        self.position += 1;
+        match count {
+            Some(count) if children.len() != count => Err(self.error_at(
+                start,
+                format!(
+                    "`{node}` expects {count} children, found {}",
+                    children.len()
+                ),
+            )),
+            None if children.is_empty() => {
+                Err(self.error_at(start, format!("`{node}` expects at least one child")))
+            }
+            _ => Ok(children),
+        }
+    }
+
+    fn pair(&mut self, node: &str) -> Result<(DynObject, DynObject), SceneError> {
+        let mut children = self.children(node, Some(2))?;
+        let second = children.pop().unwrap();
+        let first = children.pop().unwrap();
+        Ok((first, second))
+    }
+
+    fn single(&mut self, node: &str) -> Result<DynObject, SceneError> {
+        Ok(self.children(node, Some(1))?.pop().unwrap())
+    }
+
+    fn transformation(&mut self) -> Result<Affine3A, SceneError> {
+        let mut transformation = Affine3A::IDENTITY;
+        while self.peek() != Some("{") {
+            let start = self.position;
+            let op = match self.next("a transform operation or `{`")? {
+                "translate" => Affine3A::from_translation(self.vector()?),
+                "rotate_x" => Affine3A::from_rotation_x(self.number()?.to_radians()),
+                "rotate_y" => Affine3A::from_rotation_y(self.number()?.to_radians()),
+                "rotate_z" => Affine3A::from_rotation_z(self.number()?.to_radians()),
+                "scale" => {
+                    let x = self.number()?;
+                    if self.is_number() {
+                        Affine3A::from_scale(vec3(x, self.number()?, self.number()?))
+                    } else {
+                        Affine3A::from_scale(Vec3::splat(x))
+                    }
+                }
+                other => {
+                    return Err(self.error_at(
+                        self.position - 1,
+                        format!("unknown transform operation `{other}`"),
+                    ));
+                }
+            };
+            if op.matrix3.determinant() == 0.0 {
+                return Err(self.error_at(start, "zero scale".to_owned()));
+            }
+            transformation = op * transformation;
+        }
+        Ok(transformation)
+    }
+
+    fn material(&mut self) -> Result<Material, SceneError> {
+        let mut material = Material::default();
+        while self.peek() != Some("{") {
+            match self.next("a material property or `{`")? {
+                "color" => material.color = self.vector()?,
+                "roughness" => material.roughness = self.number()?,
+                "specular" => material.specular = self.number()?,
+                "emission" => material.emission = self.vector()?,
+                other => {
+                    return Err(self.error_at(
+                        self.position - 1,
+                        format!("unknown material property `{other}`"),
+                    ));
+                }
+            }
+        }
+        Ok(material)
+    }
+
+    /// Consumes the optional `cutter_material` flag of a difference.
+    fn cutter_material(&mut self) -> bool {
+        let flag = self.peek() == Some("cutter_material");
+        if flag {
+            self.position += 1;
+        }
+        flag
+    }
+
+    /// Tags a primitive with the next free ID, IDs start at 1.
+    fn primitive<O>(&mut self, obj: O) -> DynObject
+    where
+        O: Object + Send + Sync + 'static,
+        O::Iter: 'static,
+    {
+        self.next_id += 1;
+        DynObject::new(CSGTag::new(obj, self.next_id))
+    }
+
+    /// Wraps a compound node so rays missing its bounding box skip the whole subtree.
+    fn bounded<O>(obj: O) -> DynObject
+    where
+        O: Object + Send + Sync + 'static,
+        O::Iter: 'static,
+    {
+        DynObject::new(CSGBounded::new(obj))
+    }
+
+    fn node(&mut self) -> Result<DynObject, SceneError> {
+        let kind = self.next("an object")?;
+        let obj = match kind {
+            "sphere" => {
+                let origin = self.vector()?;
+                let sphere = CSGSphere::new(origin, self.positive("radius")?);
+                self.primitive(sphere)
+            }
+            "cylinder" => {
+                let radius = self.positive("radius")?;
+                let cylinder = CSGCylinder::new(radius, self.positive("height")?);
+                self.primitive(cylinder)
+            }
+            "box" => {
+                let (min, max) = (self.vector()?, self.vector()?);
+                let b = if self.peek() == Some("round") {
+                    self.position += 1;
+                    CSGBox::rounded(min, max, self.positive("radius")?)
+                } else {
+                    CSGBox::new(min, max)
+                };
+                self.primitive(b)
+            }
+            "cone" => {
+                let base_radius = self.number()?;
+                let top_radius = self.number()?;
+                let cone = CSGCone::new(base_radius, top_radius, self.positive("height")?);
+                self.primitive(cone)
+            }
+            "torus" => {
+                let major_radius = self.positive("major radius")?;
+                let torus = CSGTorus::new(major_radius, self.positive("minor radius")?);
+                self.primitive(torus)
+            }
+            "halfspace" => {
+                let normal = self.vector()?;
+                if normal == Vec3::ZERO {
+                    return Err(self.error_at(self.position - 3, "zero plane normal".to_owned()));
+                }
+                let halfspace = CSGHalfSpace::new(normal, self.number()?);
+                self.primitive(halfspace)
+            }
+            "union" => {
+                let (a, b) = self.pair(kind)?;
+                Self::bounded(CSGUnion::new(a, b))
+            }
+            "intersect" => {
+                let (a, b) = self.pair(kind)?;
+                Self::bounded(CSGIntersect::new(a, b))
+            }
+            "difference" => {
+                let cutter_material = self.cutter_material();
+                let (a, b) = self.pair(kind)?;
+                let difference = CSGDifference::new(a, b);
+                Self::bounded(if cutter_material {
+                    difference.with_cutter_material()
+                } else {
+                    difference
+                })
+            }
+            "xor" => {
+                let (a, b) = self.pair(kind)?;
+                Self::bounded(CSGXor::new(a, b))
+            }
+            "vec_union" => Self::bounded(CSGVecUnion::new(self.children(kind, None)?)),
+            "vec_intersect" => Self::bounded(CSGVecIntersect::new(self.children(kind, None)?)),
+            "vec_difference" => {
+                let cutter_material = self.cutter_material();
+                let mut children = self.children(kind, None)?;
+                let base = children.remove(0);
+                let difference = CSGVecDifference::new(base, children);
+                Self::bounded(if cutter_material {
+                    difference.with_cutter_material()
+                } else {
+                    difference
+                })
+            }
+            "complement" => DynObject::new(CSGComplement::new(self.single(kind)?)),
+            "clipplane" => {
+                let normal = self.vector()?;
+                if normal == Vec3::ZERO {
+                    return Err(self.error_at(self.position - 3, "zero plane normal".to_owned()));
+                }
+                let d = self.number()?;
+                Self::bounded(CSGClipplane::new(self.single(kind)?, normal, d))
+            }
+            "transform" => {
+                let transformation = self.transformation()?;
+                Self::bounded(CSGTransform::new(self.single(kind)?, transformation))
+            }
+            "material" => {
+                let material = self.material()?;
+                DynObject::new(CSGMaterial::new(self.single(kind)?, material))
+            }
+            "use" => {
+                let name = self.next("a name")?;
+                let start = *self.defines.get(name).ok_or_else(|| {
+                    self.error_at(self.position - 1, format!("`{name}` is not defined"))
+                })?;
+                let end = self.position;
+                self.position = start;
+                let obj = self.node()?;
+                self.position = end;
+                obj
+            }
+            other => {
+                return Err(self.error_at(self.position - 1, format!("unknown object `{other}`")));
+            }
+        };
+        Ok(obj)
+    }
+
+    fn camera(&mut self, camera: &mut CameraSettings) -> Result<(), SceneError> {
+        self.expect("{")?;
+        let mut fov_position = None;
+        loop {
+            match self.next("a camera property or `}`")? {
+                "eye" => camera.eye = self.vector()?,
+                "target" => camera.target = self.vector()?,
+                "up" => camera.up = self.vector()?,
+                "fov" => {
+                    camera.fov = self.positive("fov")?;
+                    fov_position = Some(self.position - 1);
+                }
+                "height" => camera.height = self.positive("height")?,
+                "projection" => {
+                    camera.projection = match self.next("a projection")? {
+                        "perspective" => Projection::Perspective,
+                        "orthographic" => Projection::Orthographic,
+                        "fisheye" => Projection::Fisheye,
+                        "equirectangular" => Projection::Equirectangular,
+                        other => {
+                            return Err(self.error_at(
+                                self.position - 1,
+                                format!("unknown projection `{other}`"),
+                            ));
+                        }
+                    }
+                }
+                "}" => {
+                    // The projection may follow the fov, so check once both are known
+                    if let Some(position) = fov_position
+                        && camera.projection == Projection::Perspective
+                        && camera.fov >= 180.0
+                    {
+                        return Err(self.error_at(
+                            position,
+                            format!("perspective fov must be below 180, found {}", camera.fov),
+                        ));
+                    }
+                    return Ok(());
+                }
+                other => {
+                    return Err(self.error_at(
+                        self.position - 1,
+                        format!("unknown camera property `{other}`"),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn scene(&mut self) -> Result<Scene, SceneError> {
+        let mut camera = CameraSettings::default();
+        let mut lights = vec![];
+        let mut objects = vec![];
+        while let Some(item) = self.peek() {
+            self.position += 1;
+            match item {
+                "camera" => self.camera(&mut camera)?,
+                "light" => lights.push(self.vector()?),
+                "define" => {
+                    let name = self.next("a name")?;
+                    if self.defines.contains_key(name) {
+                        return Err(self
+                            .error_at(self.position - 1, format!("`{name}` is already defined")));
+                    }
+                    // Only check the definition here, IDs are handed out on `use`
+                    let start = self.position;
+                    let next_id = self.next_id;
+                    self.node()?;
+                    self.next_id = next_id;
+                    self.defines.insert(name, start);
+                }
+                "object" => objects.push(self.node()?),
+                other => {
+                    return Err(self.error_at(
+                        self.position - 1,
+                        format!(
+                            "expected `camera`, `light`, `define` or `object`, found `{other}`"
+                        ),
+                    ));
+                }
+            }
+        }
+        let object = match objects.len() {
+            0 => return Err(self.error("scene does not contain any `object`".to_owned())),
+            1 => objects.pop().unwrap(),
+            _ => DynObject::new(CSGVecUnion::new(objects)),
+        };
+        Ok(Scene {
+            object,
+            camera,
+            lights,
+            primitives: self.next_id,
+        })
+    }
+}
+
+impl Scene {
+    /// Parses a scene from `source`, `file` is only used for error messages.
+    pub fn parse(source: &str, file: &str) -> Result<Self, SceneError> {
+        Parser {
+            file,
+            tokens: tokenize(source),
+            position: 0,
+            defines: HashMap::new(),
+            next_id: 0,
+        }
+        .scene()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let source = std::fs::read_to_string(path).map_err(|e| SceneError {
+            file: file.clone(),
+            line: 0,
+            column: 0,
+            message: e.to_string(),
+        })?;
+        Self::parse(&source, &file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, vec3};
+
+    use crate::{objects::Object, scene::Scene};
+
+    fn first_hit(scene: &Scene, origin: Vec3, direction: Vec3) -> Option<f32> {
+        scene
+            .object
+            .trace(origin, direction)
+            .next()
+            .map(|hit| hit.t)
+    }
+
+    #[test]
+    fn parse_demo() {
+        let scene = Scene::parse(include_str!("../scenes/demo.scene"), "demo.scene").unwrap();
+        assert_eq!(scene.lights, vec![vec3(200.0, 200.0, -200.0)]);
+        // Down the vertical hole, off its axis so no two boundaries coincide
+        assert_eq!(first_hit(&scene, vec3(0.05, 5.0, 0.1), Vec3::NEG_Y), None);
+        // The cut face of the clipped sphere
+        let t = first_hit(&scene, vec3(0.6, 5.0, 0.3), Vec3::NEG_Y).unwrap();
+        assert!((t - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn parse_define() {
+        let scene = Scene::parse(
+            "define ball sphere 0 0 0 1\nobject transform translate 2 0 0 { use ball }",
+            "test",
+        )
+        .unwrap();
+        let t = first_hit(&scene, vec3(-5.0, 0.0, 0.0), Vec3::X).unwrap();
+        assert!((t - 6.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn report_position() {
+        let error = Scene::parse(
+            "object union {\n  sphere 0 0 0 1\n  spere 0 0 0 1\n}",
+            "test",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error.to_string(), "test:3:3: unknown object `spere`");
+
+        let error = Scene::parse("object union {\n  sphere 0 0 0 1\n}", "test")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:1:14: `union` expects 2 children, found 1"
+        );
+
+        let error = Scene::parse("object sphere 0 0 zero 1", "test")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:1:19: expected a number, found `zero`"
+        );
+
+        let error = Scene::parse("object use hole", "test").err().unwrap();
+        assert_eq!(error.to_string(), "test:1:12: `hole` is not defined");
+
+        let error = Scene::parse("light 1 2 3", "test").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:1:11: scene does not contain any `object`"
+        );
+    }
+
+    #[test]
+    fn degenerate_camera_and_transforms() {
+        let error = Scene::parse("object transform scale 0 { sphere 0 0 0 1 }", "test")
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "test:1:18: zero scale");
+
+        let error = Scene::parse(
+            "object transform rotate_x 30\n  scale 1 0 2 { sphere 0 0 0 1 }",
+            "test",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error.to_string(), "test:2:3: zero scale");
+
+        let error = Scene::parse("camera { fov 180 }\nobject sphere 0 0 0 1", "test")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:1:14: perspective fov must be below 180, found 180"
+        );
+
+        // Only a perspective camera is limited, also when the projection comes later
+        let error = Scene::parse(
+            "camera { projection fisheye  fov 200  projection perspective }\n\
+             object sphere 0 0 0 1",
+            "test",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:1:34: perspective fov must be below 180, found 200"
+        );
+        let scene = Scene::parse(
+            "camera { fov 200  projection fisheye }\nobject sphere 0 0 0 1",
+            "test",
+        )
+        .unwrap();
+        assert_eq!(scene.camera.fov, 200.0);
+    }
+
+    #[test]
+    fn primitive_ids() {
+        let scene = Scene::parse(
+            "define ball sphere 0 0 0 1\n\
+             object vec_union { use ball  transform translate 3 0 0 { use ball }  box 5 -1 -1 6 1 1 }",
+            "test",
+        )
+        .unwrap();
+        let ids: Vec<u32> = scene
+            .object
+            .trace(vec3(-5.0, 0.0, 0.0), Vec3::X)
+            .map(|hit| hit.id)
+            .collect();
+        assert_eq!(ids, vec![1, 1, 2, 2, 3, 3]);
+        assert_eq!(scene.primitives, 3);
+
+        let error = Scene::parse("define a sphere 0 0 0 1\ndefine a use a", "test")
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "test:2:8: `a` is already defined");
+    }
+
+    #[test]
+    fn materials() {
+        let source = |flag| {
+            format!(
+                "object material color 1 0 0 {{ difference {flag} {{\n\
+                   sphere 0 0 0 1\n\
+                   material color 0 1 0 specular 0.5 {{ sphere 1 0 0 0.5 }}\n\
+                 }} }}"
+            )
+        };
+        let colors = |scene: Scene| -> Vec<Vec3> {
+            scene
+                .object
+                .trace(vec3(-5.0, 0.0, 0.0), Vec3::X)
+                .map(|hit| hit.material.unwrap().color)
+                .collect()
+        };
+        let scene = Scene::parse(&source(""), "test").unwrap();
+        assert_eq!(colors(scene), vec![Vec3::X, Vec3::X]);
+        let scene = Scene::parse(&source("cutter_material"), "test").unwrap();
+        assert_eq!(colors(scene), vec![Vec3::X, Vec3::Y]);
+
+        let error = Scene::parse("object material shine 1 { sphere 0 0 0 1 }", "test")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:1:17: unknown material property `shine`"
+        );
+    }
+
+    #[test]
+    fn hit_records() {
+        let scene = Scene::parse(
+            "object union {\n\
+               transform translate 10 0 0 { difference { sphere 0 0 0 1  sphere 1 0 0 0.5 } }\n\
+               sphere 0 0 0 1\n\
+             }",
+            "test",
+        )
+        .unwrap();
+        let hits: Vec<_> = scene.object.trace(vec3(-5.0, 0.0, 0.0), Vec3::X).collect();
+        let entering: Vec<bool> = hits.iter().map(|hit| hit.entering).collect();
+        assert_eq!(entering, vec![true, false, true, false]);
+        let ids: Vec<u32> = hits.iter().map(|hit| hit.id).collect();
+        assert_eq!(ids, vec![3, 3, 1, 2]);
+        // Points stay in the space of the primitive, before the translation
+        let points: Vec<Vec3> = hits.iter().map(|hit| hit.point).collect();
+        let expected = [Vec3::NEG_X, Vec3::X, Vec3::NEG_X, vec3(0.5, 0.0, 0.0)];
+        for (p, e) in points.iter().zip(expected) {
+            assert!(p.distance(e) < 1e-5, "{points:?}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Per-thread pool of allocations, so tracing reuses the memory of earlier rays instead
+//! of allocating for every one of them.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+
+/// Spare boxes, grouped by the type they hold.
+type Pool = Vec<(TypeId, Vec<Box<dyn Any>>)>;
+
+thread_local! {
+    static POOL: RefCell<Pool> = const { RefCell::new(Vec::new()) };
+}
+
+/// A spare box for a `T` from the current thread's pool, holding whatever it was
+/// recycled with.
+pub fn take<T: 'static>() -> Option<Box<T>> {
+    POOL.with_borrow_mut(|pool| {
+        let (_, spares) = pool.iter_mut().find(|(id, _)| *id == TypeId::of::<T>())?;
+        spares.pop()?.downcast().ok()
+    })
+}
+
+/// Hands `spare` to the current thread's pool. Whatever it holds is kept alive until
+/// it is taken again, so clear it first.
+pub fn recycle<T: 'static>(spare: Box<T>) {
+    POOL.with_borrow_mut(|pool| {
+        let id = TypeId::of::<T>();
+        let index = match pool.iter().position(|(i, _)| *i == id) {
+            Some(index) => index,
+            None => {
+                pool.push((id, vec![]));
+                pool.len() - 1
+            }
+        };
+        pool[index].1.push(spare);
+    })
+}
+
+/// A `Vec` from the current thread's pool, cleared and handed back when dropped.
+pub struct ScratchVec<T: 'static> {
+    /// Boxed, so it joins the pool without another allocation. Only `None` while being
+    /// dropped.
+    #[allow(clippy::box_collection)]
+    vec: Option<Box<Vec<T>>>,
+}
+
+impl<T: 'static> ScratchVec<T> {
+    pub fn new() -> Self {
+        Self {
+            vec: Some(take().unwrap_or_default()),
+        }
+    }
+}
+
+impl<T: 'static> Default for ScratchVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> Deref for ScratchVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        self.vec.as_ref().unwrap()
+    }
+}
+
+impl<T: 'static> DerefMut for ScratchVec<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        self.vec.as_mut().unwrap()
+    }
+}
+
+impl<T: 'static> Drop for ScratchVec<T> {
+    fn drop(&mut self) {
+        let mut vec = self.vec.take().unwrap();
+        // The elements may hand back scratch of their own, which must happen before
+        // the pool is borrowed here
+        vec.clear();
+        recycle(vec);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scratch::ScratchVec;
+
+    #[test]
+    fn reuses_vecs() {
+        let mut a: ScratchVec<u32> = ScratchVec::new();
+        a.extend([1, 2, 3]);
+        let pointer = a.as_ptr();
+        drop(a);
+        let b: ScratchVec<u32> = ScratchVec::new();
+        assert!(b.is_empty());
+        assert_eq!(b.as_ptr(), pointer);
+        // Taken, so a second one needs fresh memory
+        let c: ScratchVec<u32> = ScratchVec::new();
+        assert_ne!(c.as_ptr(), pointer);
+    }
+}
@@ -0,0 +1,29 @@
+//! Fixtures shared by the unit tests.
+
+use glam::{Vec3, vec3};
+
+use crate::{hit::Hit, objects::Object};
+
+/// Asserts that the ray from `origin` along `direction` crosses the boundary of `obj` at
+/// the distances `expected`, with the given outward normals, alternately entering and
+/// leaving.
+pub fn assert_hits(obj: &impl Object, origin: Vec3, direction: Vec3, expected: &[(f32, Vec3)]) {
+    let hits: Vec<Hit> = obj.trace(origin, direction).collect();
+    assert_eq!(hits.len(), expected.len(), "{hits:?}");
+    for (i, (hit, &(t, normal))) in hits.iter().zip(expected).enumerate() {
+        assert!((hit.t - t).abs() < 1e-5, "{hits:?}");
+        assert!(hit.normal.distance(normal) < 1e-5, "{hits:?}");
+        assert_eq!(hit.entering, i % 2 == 0, "{hits:?}");
+    }
+}
+
+/// The `i`th point of a low discrepancy sequence filling the unit cube. The irrational
+/// steps keep the points off axis aligned planes through simple coordinates.
+pub fn scattered(i: usize) -> Vec3 {
+    let i = i as f32;
+    vec3(
+        (i * 0.618034).fract(),
+        (i * 0.414214).fract(),
+        (i * 0.732051).fract(),
+    )
+}
@@ -0,0 +1,55 @@
+//! Tracing must not allocate once the scratch pool is warmed up.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use csg_renderer::{objects::Object, scene::Scene};
+use glam::{Vec3, vec3};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[test]
+fn tracing_does_not_allocate() {
+    let demo = Scene::parse(include_str!("../scenes/demo.scene"), "demo.scene").unwrap();
+    // Solving the torus' quartic goes through the polynomial root finder
+    let torus = Scene::parse("object transform rotate_x 30 { torus 1 0.25 }", "torus").unwrap();
+    for scene in [demo, torus] {
+        let directions: Vec<Vec3> = (0..256)
+            .map(|i| {
+                let (x, y) = ((i % 16) as f32 / 15.0, (i / 16) as f32 / 15.0);
+                (vec3(x * 2.0 - 1.0, y * 2.0 - 1.0, 0.0) - scene.camera.eye).normalize()
+            })
+            .collect();
+        let origin = scene.camera.eye;
+        let trace = || {
+            directions
+                .iter()
+                .map(|&d| scene.object.trace(origin, d).count())
+                .sum::<usize>()
+        };
+        let crossings = trace();
+        assert!(crossings > 0);
+
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        assert_eq!(trace(), crossings);
+        assert_eq!(ALLOCATIONS.load(Ordering::Relaxed) - before, 0);
+    }
+}
@@ -0,0 +1,112 @@
+//! Renders small reference scenes and compares them with the images checked in under
+//! `tests/golden`. Run with `UPDATE_GOLDEN=1` to write the current renders as the new
+//! references after an intended change, and look at them before committing.
+
+use std::path::{Path, PathBuf};
+
+use csg_renderer::{
+    render::{RenderMode, RenderSettings, render},
+    scene::Scene,
+};
+use image::{Rgb, RgbImage};
+
+/// Largest difference allowed in any channel of a pixel.
+const TOLERANCE: u8 = 4;
+
+/// Pixels allowed beyond `TOLERANCE`, for silhouettes that rounding moves by a pixel.
+const MAX_BAD_PIXELS: usize = 4;
+
+const SIZE: u32 = 64;
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// Renders `scene` and compares it with `tests/golden/<name>.png`. On a mismatch the
+/// render and a diff, with the bad pixels in red, are written next to the test binary.
+fn check(name: &str, scene: &Scene, mode: RenderMode) {
+    let settings = RenderSettings {
+        width: SIZE,
+        height: SIZE,
+        mode,
+        ..RenderSettings::default()
+    };
+    let actual = render(scene, &settings);
+    let path = golden_dir().join(format!("{name}.png"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.save(&path).unwrap();
+        return;
+    }
+    let expected = match image::open(&path) {
+        Ok(expected) => expected.into_rgb8(),
+        Err(error) => panic!(
+            "cannot read {}: {error}, run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        ),
+    };
+    assert_eq!(expected.dimensions(), actual.dimensions(), "{name}");
+
+    let bad = |x: u32, y: u32| {
+        let (a, e) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        a.0.iter().zip(e.0).any(|(&a, e)| a.abs_diff(e) > TOLERANCE)
+    };
+    let bad_pixels = (0..SIZE * SIZE).filter(|i| bad(i % SIZE, i / SIZE)).count();
+    if bad_pixels > MAX_BAD_PIXELS {
+        let diff = RgbImage::from_fn(SIZE, SIZE, |x, y| {
+            if bad(x, y) {
+                Rgb([255, 0, 0])
+            } else {
+                // The reference, dimmed so the red stands out
+                Rgb(expected.get_pixel(x, y).0.map(|c| c / 3))
+            }
+        });
+        let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        std::fs::create_dir_all(&out).unwrap();
+        actual.save(out.join(format!("{name}.png"))).unwrap();
+        diff.save(out.join(format!("{name}.diff.png"))).unwrap();
+        panic!(
+            "{name}: {bad_pixels} pixels differ from {}, see the render and diff in {}",
+            path.display(),
+            out.display()
+        );
+    }
+}
+
+fn scene(name: &str) -> Scene {
+    Scene::load(golden_dir().join(format!("{name}.scene"))).unwrap()
+}
+
+#[test]
+fn sphere() {
+    check("sphere", &scene("sphere"), RenderMode::Shaded);
+}
+
+#[test]
+fn drilled_sphere() {
+    let demo = Scene::parse(include_str!("../scenes/demo.scene"), "demo.scene").unwrap();
+    check("drilled_sphere", &demo, RenderMode::Shaded);
+}
+
+#[test]
+fn materials() {
+    check("materials", &scene("materials"), RenderMode::Shaded);
+}
+
+#[test]
+fn clipped_cylinder() {
+    check(
+        "clipped_cylinder",
+        &scene("clipped_cylinder"),
+        RenderMode::Shaded,
+    );
+}
+
+#[test]
+fn booleans() {
+    check("booleans", &scene("booleans"), RenderMode::Shaded);
+}
+
+#[test]
+fn ellipsoids() {
+    check("ellipsoids", &scene("ellipsoids"), RenderMode::Normals);
+}
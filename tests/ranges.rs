@@ -0,0 +1,212 @@
+//! Property tests for the range combinators: random interval lists go in, and the
+//! result must contain exactly the points the boolean operation says it does.
+
+use csg_renderer::{
+    hit::Hit, range_difference::RangeDifference, range_intersect::RangeIntersect,
+    range_union::RangeUnion, range_vec_difference::RangeVecDifference,
+    range_vec_intersect::RangeVecIntersect, range_vec_union::RangeVecUnion, range_xor::RangeXor,
+};
+use glam::Vec3;
+use proptest::prelude::*;
+
+/// Spacing of the boundaries, coarse so that ties within and between lists are common.
+const STEP: f32 = 0.5;
+
+/// Boundary at grid step `step`, the first and last step stand for the infinities that
+/// unbounded solids produce.
+fn boundary(step: u8) -> f32 {
+    match step {
+        0 => f32::NEG_INFINITY,
+        13 => f32::INFINITY,
+        _ => step as f32 * STEP,
+    }
+}
+
+/// Boundaries at the sorted distances `t`, alternating between entries and exits.
+fn list(t: impl IntoIterator<Item = f32>) -> Vec<Hit> {
+    t.into_iter()
+        .enumerate()
+        .map(|(i, t)| Hit {
+            entering: i % 2 == 0,
+            ..Hit::new(t, Vec3::X)
+        })
+        .collect()
+}
+
+/// Sorted boundaries alternating between entries and exits, including empty intervals,
+/// intervals touching their neighbors and unbounded ones.
+fn intervals() -> impl Strategy<Value = Vec<Hit>> {
+    prop::collection::vec(0u8..14, 0..10).prop_map(|mut steps| {
+        steps.sort();
+        steps.truncate(steps.len() / 2 * 2);
+        list(steps.into_iter().map(boundary))
+    })
+}
+
+/// Whether `t` lies strictly inside one of the intervals.
+fn inside(hits: &[Hit], t: f32) -> bool {
+    hits.chunks(2).any(|pair| pair[0].t < t && t < pair[1].t)
+}
+
+/// Checks that `result` is a valid list of intervals containing the points between
+/// the grid of boundaries exactly when `expected` says so. The boundaries themselves
+/// are not sampled, whether an interval includes its ends is up to the surface, but
+/// no interval may be empty.
+fn check(
+    result: impl Iterator<Item = Hit>,
+    expected: impl Fn(f32) -> bool,
+) -> Result<(), TestCaseError> {
+    let result: Vec<Hit> = result.collect();
+    prop_assert_eq!(result.len() % 2, 0, "{:?}", result);
+    for (i, pair) in result.windows(2).enumerate() {
+        prop_assert!(pair[0].t <= pair[1].t, "unsorted at {}: {:?}", i, result);
+    }
+    for (i, pair) in result.chunks(2).enumerate() {
+        prop_assert!(pair[0].t < pair[1].t, "empty interval {}: {:?}", i, result);
+    }
+    for (i, hit) in result.iter().enumerate() {
+        prop_assert_eq!(hit.entering, i % 2 == 0, "at {}: {:?}", i, result);
+    }
+    for k in 0..14 {
+        let t = (k as f32 + 0.5) * STEP;
+        prop_assert_eq!(inside(&result, t), expected(t), "at {}: {:?}", t, result);
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn union(a in intervals(), b in intervals()) {
+        let result = RangeUnion::new(a.clone().into_iter(), b.clone().into_iter());
+        check(result, |t| inside(&a, t) || inside(&b, t))?;
+    }
+
+    #[test]
+    fn intersect(a in intervals(), b in intervals()) {
+        let result = RangeIntersect::new(a.clone().into_iter(), b.clone().into_iter());
+        check(result, |t| inside(&a, t) && inside(&b, t))?;
+    }
+
+    #[test]
+    fn difference(a in intervals(), b in intervals()) {
+        let result = RangeDifference::new(a.clone().into_iter(), b.clone().into_iter());
+        check(result, |t| inside(&a, t) && !inside(&b, t))?;
+    }
+
+    #[test]
+    fn xor(a in intervals(), b in intervals()) {
+        let result = RangeXor::new(a.clone().into_iter(), b.clone().into_iter());
+        check(result, |t| inside(&a, t) != inside(&b, t))?;
+    }
+
+    #[test]
+    fn vec_union(lists in prop::collection::vec(intervals(), 0..5)) {
+        let result = RangeVecUnion::new(lists.iter().map(|l| l.clone().into_iter()));
+        check(result, |t| lists.iter().any(|l| inside(l, t)))?;
+    }
+
+    #[test]
+    fn vec_intersect(lists in prop::collection::vec(intervals(), 1..5)) {
+        let result = RangeVecIntersect::new(lists.iter().map(|l| l.clone().into_iter()));
+        check(result, |t| lists.iter().all(|l| inside(l, t)))?;
+    }
+
+    #[test]
+    fn vec_difference(base in intervals(), cutters in prop::collection::vec(intervals(), 0..5)) {
+        let result = RangeVecDifference::new(
+            base.clone().into_iter(),
+            cutters.iter().map(|l| l.clone().into_iter()),
+        );
+        check(result, |t| inside(&base, t) && !cutters.iter().any(|l| inside(l, t)))?;
+    }
+}
+
+/// Operands sharing boundaries: identical, touching, and sharing either end, bounded
+/// and unbounded.
+fn coincident() -> Vec<(Vec<Hit>, Vec<Hit>)> {
+    let inf = f32::INFINITY;
+    [
+        (vec![1.0, 2.0], vec![1.0, 2.0]),
+        (vec![1.0, 2.0, 3.0, 4.0], vec![1.0, 2.0, 3.0, 4.0]),
+        (vec![1.0, 2.0], vec![2.0, 3.0]),
+        (vec![2.0, 3.0], vec![1.0, 2.0]),
+        (vec![1.0, 3.0], vec![1.0, 2.0]),
+        (vec![1.0, 3.0], vec![2.0, 3.0]),
+        (vec![1.0, 2.0, 3.0, 4.0], vec![2.0, 3.0]),
+        (vec![-inf, 2.0], vec![-inf, 2.0]),
+        (vec![-inf, 2.0], vec![2.0, inf]),
+        (vec![-inf, inf], vec![-inf, inf]),
+    ]
+    .into_iter()
+    .flat_map(|(a, b)| [(list(a.clone()), list(b.clone())), (list(b), list(a))])
+    .collect()
+}
+
+#[test]
+fn coincident_boundaries() {
+    let ok = |name: &str, a: &[Hit], b: &[Hit], result: Result<(), TestCaseError>| {
+        if let Err(error) = result {
+            panic!("{name} of {a:?} and {b:?}: {error}");
+        }
+    };
+    for (a, b) in coincident() {
+        let (a, b) = (&a[..], &b[..]);
+        let iter = |l: &[Hit]| Vec::from(l).into_iter();
+        ok(
+            "union",
+            a,
+            b,
+            check(RangeUnion::new(iter(a), iter(b)), |t| {
+                inside(a, t) || inside(b, t)
+            }),
+        );
+        ok(
+            "intersect",
+            a,
+            b,
+            check(RangeIntersect::new(iter(a), iter(b)), |t| {
+                inside(a, t) && inside(b, t)
+            }),
+        );
+        ok(
+            "difference",
+            a,
+            b,
+            check(RangeDifference::new(iter(a), iter(b)), |t| {
+                inside(a, t) && !inside(b, t)
+            }),
+        );
+        ok(
+            "xor",
+            a,
+            b,
+            check(RangeXor::new(iter(a), iter(b)), |t| {
+                inside(a, t) != inside(b, t)
+            }),
+        );
+        ok(
+            "vec_union",
+            a,
+            b,
+            check(RangeVecUnion::new([iter(a), iter(b), iter(a)]), |t| {
+                inside(a, t) || inside(b, t)
+            }),
+        );
+        ok(
+            "vec_intersect",
+            a,
+            b,
+            check(RangeVecIntersect::new([iter(a), iter(b), iter(a)]), |t| {
+                inside(a, t) && inside(b, t)
+            }),
+        );
+        ok(
+            "vec_difference",
+            a,
+            b,
+            check(RangeVecDifference::new(iter(a), [iter(b), iter(b)]), |t| {
+                inside(a, t) && !inside(b, t)
+            }),
+        );
+    }
+}